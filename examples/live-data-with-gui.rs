@@ -1,10 +1,47 @@
 use audio_visualizer::dynamic::live_input::AudioDevAndCfg;
 use audio_visualizer::dynamic::window_top_btm::{TransformFn, open_window_connect_audio};
-use lowpass_filter::lowpass_filter;
+use lowpass_filter::{InitialState, LowpassFilter, LowpassStream};
+use std::cell::RefCell;
+
+/// Cutoff frequency of the lowpass filter.
+const CUTOFF_FREQUENCY_HZ: f32 = 120.0;
 
 /// Example that creates a live visualization of realtime audio data
 /// through a lowpass filter. **Execute this with `--release`, otherwise it is very laggy!**.
 fn main() {
+    // fall back to the default input audio device (e.g. microphone)
+    let input = AudioDevAndCfg::new(None, None);
+    let sample_rate_hz = input.cfg().sample_rate.0 as f32;
+
+    // The filter is primed with the first sample, so that the oldest audio
+    // in the window doesn't fade in.
+    let stream = RefCell::new(LowpassStream::from_filter(
+        LowpassFilter::new(sample_rate_hz, CUTOFF_FREQUENCY_HZ)
+            .with_initial_state(InitialState::FirstSample),
+    ));
+    let transform = |latest: &[f32], sampling_rate: f32| {
+        // Each refresh gets the latest window of audio, but not how many
+        // samples of it are new. So the whole window is filtered again,
+        // which keeps the output continuous.
+        let mut stream = stream.borrow_mut();
+        stream.reset();
+        let mut output = latest.to_vec();
+        stream
+            .process_with_sample_rate(&mut output, sampling_rate)
+            .unwrap();
+
+        // Same x-axis as the unfiltered audio: the newest sample is at 0 s.
+        let time_per_sample = 1.0 / f64::from(sampling_rate);
+        let timeshift = output.len() as f64 * time_per_sample;
+        output
+            .iter()
+            .enumerate()
+            // Drawing every point is too expensive.
+            .step_by(4)
+            .map(|(i, &amplitude)| (time_per_sample * i as f64 - timeshift, f64::from(amplitude)))
+            .collect()
+    };
+
     open_window_connect_audio(
         "Live Audio Lowpass Filter View",
         None,
@@ -13,13 +50,8 @@ fn main() {
         None,
         "time (seconds)",
         "Amplitude (with Lowpass filter)",
-        // fall back to the default input audio device (e.g. microphone)
-        AudioDevAndCfg::new(None, None),
+        input,
         // lowpass filter
-        TransformFn::Basic(|x, sampling_rate| {
-            let mut data = x.to_vec();
-            lowpass_filter(&mut data, sampling_rate, 120.0);
-            data
-        }),
+        TransformFn::Complex(&transform),
     );
 }
@@ -22,12 +22,17 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 //! Minimal example how to use this crate/how to apply low pass filter.
-use lowpass_filter::lowpass_filter;
+use lowpass_filter::{LowpassFilter, Unbounded, lowpass_filter};
 
 /// Minimal example how to use this crate/how to apply low pass filter.
 fn main() {
     // read this from MP3 for example
-    let mut mono_audio_data = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0];
+    let mut mono_audio_data = [0.0, 0.5, -0.25, 1.0, -1.0, 0.125];
     // mutates the input buffer
     lowpass_filter(&mut mono_audio_data, 44100.0, 120.0);
+
+    // data that is not in range -1.0..=1.0, e.g., ADC counts
+    let mut adc_data = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0];
+    let mut filter = LowpassFilter::new(44100.0, 120.0).with_range_policy(Unbounded);
+    filter.process_in_place(&mut adc_data);
 }
@@ -1,15 +1,15 @@
-use crate::test_util::{
-    f32_sample_to_i16, i16_sample_to_f32, read_wav_to_mono, target_dir_test_artifacts,
-    write_wav_file,
-};
+use crate::test_util::target_dir_test_artifacts;
 use audio_visualizer::Channels;
 use audio_visualizer::spectrum::plotters_png_file::spectrum_static_plotters_png_visualize;
 use audio_visualizer::waveform::png_file::waveform_static_png_visualize;
+use hound::{SampleFormat, WavSpec};
+use itertools::Itertools;
 use lowpass_filter::lowpass_filter;
+use lowpass_filter::sample::{PcmSample, Scaling, stereo_to_mono};
 use spectrum_analyzer::scaling::scale_to_zero_to_one;
 use spectrum_analyzer::windows::hann_window;
 use spectrum_analyzer::{FrequencyLimit, samples_fft_to_spectrum};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[path = "../src/test_util.rs"]
 mod test_util;
@@ -38,7 +38,7 @@ fn main() {
     let samples_unprocessed_f32 = samples_unprocessed_i16
         .iter()
         .copied()
-        .map(i16_sample_to_f32)
+        .map(|x| x.to_float(Scaling::Symmetric))
         .collect::<Vec<_>>();
 
     // Store plotted spectrum before any processing
@@ -66,7 +66,7 @@ fn main() {
     let samples_processed_i16 = samples_processed_f32
         .iter()
         .copied()
-        .map(f32_sample_to_i16)
+        .map(|x| i16::from_float_saturating(x, Scaling::Symmetric))
         .collect::<Vec<_>>();
 
     // add suffix to path
@@ -115,3 +115,55 @@ fn samples_to_spectrum_and_plot(audio_data: &[f32], sampling_rate: f32, filename
         filename,
     );
 }
+
+/// Reads a WAV file to mono audio. Returns the samples as mono audio.
+/// Additionally, it returns the sampling rate of the file.
+fn read_wav_to_mono<T: AsRef<Path>>(file: T) -> (Vec<i16>, WavSpec) {
+    let mut reader = hound::WavReader::open(file).unwrap();
+    let header = reader.spec();
+
+    // owning vector with original data in i16 format
+    let data = reader
+        .samples::<i16>()
+        .map(|s| s.unwrap())
+        .collect::<Vec<_>>();
+
+    if header.channels == 1 {
+        (data, header)
+    } else if header.channels == 2 {
+        let data = data
+            .into_iter()
+            .chunks(2)
+            .into_iter()
+            .map(|mut lr| {
+                let l = lr.next().unwrap();
+                let r = lr
+                    .next()
+                    .expect("should have an even number of LRLR samples");
+                stereo_to_mono(l, r)
+            })
+            .collect::<Vec<_>>();
+        (data, header)
+    } else {
+        panic!("unsupported format!");
+    }
+}
+
+/// Writes a WAV file as mono.
+fn write_wav_file(path: &Path, samples: &[i16], sample_rate: u32) {
+    let mut wav_writer = hound::WavWriter::create(
+        path,
+        WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        },
+    )
+    .unwrap();
+
+    for &sample in samples {
+        wav_writer.write_sample(sample).unwrap()
+    }
+    wav_writer.finalize().unwrap();
+}
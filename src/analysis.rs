@@ -0,0 +1,264 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for analyzing signals, e.g., to verify the effect of a filter on
+//! your own data.
+//!
+//! All functions take normalized samples, i.e., full scale is `1.0`. They
+//! are `no_std` compatible and accumulate with `f64` precision, regardless
+//! of the sample type. Levels in dBFS are relative to full scale:
+//! `20 * log10(amplitude)`, so a full-scale square wave has an RMS of 0 dBFS
+//! and a full-scale sine an RMS of about -3.01 dBFS.
+//!
+//! # Example
+//! ```rust
+//! use lowpass_filter::analysis::{attenuation_db, rms_dbfs};
+//! use lowpass_filter::LowpassFilter;
+//!
+//! let before = (0..44100)
+//!     .map(|i| (i as f32 * 0.9).sin() * 0.5)
+//!     .collect::<Vec<_>>();
+//! let mut after = before.clone();
+//! LowpassFilter::new(44100.0, 500.0).process_in_place(&mut after);
+//!
+//! assert!(rms_dbfs(&before) > -10.0);
+//! assert!(attenuation_db(&before, &after) > 20.0);
+//! ```
+
+use crate::{Sample, math};
+use core::f64::consts::LOG10_E;
+
+/// Returns the energy of the signal, i.e., the sum of the squared samples.
+#[must_use]
+pub fn energy<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(energy_f64(samples))
+}
+
+/// Returns the mean power of the signal, i.e., the mean of the squared
+/// samples. It is zero for an empty signal.
+#[must_use]
+pub fn mean_power<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(mean_power_f64(samples))
+}
+
+/// Returns the root mean square (RMS) of the signal, i.e., the square root
+/// of the [`mean_power`]. It is zero for an empty signal.
+#[must_use]
+pub fn rms<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(math::sqrt(mean_power_f64(samples)))
+}
+
+/// Returns the peak of the signal, i.e., the largest absolute value. It is
+/// zero for an empty signal and NaN if a sample is NaN.
+#[must_use]
+pub fn peak<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(peak_f64(samples))
+}
+
+/// Returns the crest factor of the signal, i.e., the ratio of the [`peak`]
+/// to the [`rms`], or `None` for silence and empty signals.
+///
+/// It is `1.0` for a square wave and `√2` for a sine.
+#[must_use]
+pub fn crest_factor<T: Sample>(samples: &[T]) -> Option<T> {
+    let rms = math::sqrt(mean_power_f64(samples));
+    (rms != 0.0).then(|| T::from_f64(peak_f64(samples) / rms))
+}
+
+/// Returns the [`rms`] of the signal in dBFS. It is negative infinity for
+/// silence and empty signals.
+#[must_use]
+pub fn rms_dbfs<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(power_to_db(mean_power_f64(samples)))
+}
+
+/// Returns the [`peak`] of the signal in dBFS. It is negative infinity for
+/// silence and empty signals.
+#[must_use]
+pub fn peak_dbfs<T: Sample>(samples: &[T]) -> T {
+    T::from_f64(amplitude_to_db(peak_f64(samples)))
+}
+
+/// Converts an amplitude to dBFS, i.e., `20 * log10(|amplitude|)`.
+#[must_use]
+pub fn amplitude_to_dbfs<T: Sample>(amplitude: T) -> T {
+    T::from_f64(amplitude_to_db(amplitude.to_f64()))
+}
+
+/// Converts dBFS to an amplitude, i.e., `10^(dbfs / 20)`. This is the
+/// inverse of [`amplitude_to_dbfs`] for positive amplitudes.
+#[must_use]
+pub fn dbfs_to_amplitude<T: Sample>(dbfs: T) -> T {
+    T::from_f64(math::exp(dbfs.to_f64() / (20.0 * LOG10_E)))
+}
+
+/// Returns how much quieter `after` is than `before` in dB, i.e., the ratio
+/// of their [`mean_power`]s. Positive values mean that the signal was
+/// attenuated.
+///
+/// The signals may have different lengths. To measure the steady-state
+/// attenuation of a filter, skip the first samples of both signals, as the
+/// filter needs a moment to settle.
+///
+/// If only `after` is silent (or empty), the result is infinity, and if
+/// only `before` is silent, it is negative infinity. If both are silent,
+/// nothing was attenuated and the result is 0 dB.
+///
+/// # Arguments
+/// - `before`: The original signal, e.g., the input of a filter.
+/// - `after`: The processed signal, e.g., the output of a filter.
+#[must_use]
+pub fn attenuation_db<T: Sample>(before: &[T], after: &[T]) -> T {
+    let before = mean_power_f64(before);
+    let after = mean_power_f64(after);
+    if before == 0.0 && after == 0.0 {
+        // -inf - -inf would be NaN
+        return T::ZERO;
+    }
+    T::from_f64(power_to_db(before) - power_to_db(after))
+}
+
+fn energy_f64<T: Sample>(samples: &[T]) -> f64 {
+    samples
+        .iter()
+        .map(|sample| sample.to_f64())
+        .map(|sample| sample * sample)
+        .sum()
+}
+
+fn mean_power_f64<T: Sample>(samples: &[T]) -> f64 {
+    if samples.is_empty() {
+        0.0
+    } else {
+        #[allow(clippy::cast_precision_loss)]
+        let len = samples.len() as f64;
+        energy_f64(samples) / len
+    }
+}
+
+fn peak_f64<T: Sample>(samples: &[T]) -> f64 {
+    samples
+        .iter()
+        .map(|sample| {
+            let sample = sample.to_f64();
+            if sample < 0.0 { -sample } else { sample }
+        })
+        .fold(0.0, |peak, sample| {
+            // keep NaN once it occurred
+            if peak > sample || peak.is_nan() {
+                peak
+            } else {
+                sample
+            }
+        })
+}
+
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    let amplitude = if amplitude < 0.0 {
+        -amplitude
+    } else {
+        amplitude
+    };
+    20.0 * LOG10_E * math::ln(amplitude)
+}
+
+fn power_to_db(power: f64) -> f64 {
+    10.0 * LOG10_E * math::ln(power)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LowpassFilter;
+    use crate::test_util::sine_wave_samples;
+    use core::f64::consts::SQRT_2;
+
+    #[test]
+    fn test_levels() {
+        let square = [1.0_f32, -1.0, 1.0, -1.0];
+        assert_eq!(energy(&square), 4.0);
+        assert_eq!(mean_power(&square), 1.0);
+        assert_eq!(rms(&square), 1.0);
+        assert_eq!(peak(&square), 1.0);
+        assert_eq!(crest_factor(&square), Some(1.0));
+        assert_eq!(rms_dbfs(&square), 0.0);
+
+        let sine = sine_wave_samples(1000.0, 44100.0);
+        assert!((rms(&sine) - 1.0 / SQRT_2).abs() < 1e-6);
+        assert!((crest_factor(&sine).unwrap() - SQRT_2).abs() < 1e-5);
+        assert!((rms_dbfs(&sine) + 3.0103).abs() < 1e-4);
+        // the samples miss the exact peak by up to half a sample
+        assert!((-0.03..=0.0).contains(&peak_dbfs(&sine)));
+
+        let empty: [f64; 0] = [];
+        assert_eq!(mean_power(&empty), 0.0);
+        assert_eq!(peak(&empty), 0.0);
+        assert_eq!(crest_factor(&empty), None);
+        assert_eq!(crest_factor(&[0.0_f64; 4]), None);
+        assert_eq!(rms_dbfs(&empty), f64::NEG_INFINITY);
+
+        assert_eq!(peak(&[0.5, -0.75, 0.25]), 0.75);
+        assert!(peak(&[0.5, f64::NAN, 0.25]).is_nan());
+        assert!(mean_power(&[0.5, f64::NAN]).is_nan());
+    }
+
+    #[test]
+    fn test_dbfs_conversions() {
+        assert_eq!(amplitude_to_dbfs(1.0), 0.0);
+        assert!((amplitude_to_dbfs(0.5_f64) + 6.0206).abs() < 1e-4);
+        assert!((amplitude_to_dbfs(-0.1_f64) + 20.0).abs() < 1e-12);
+        assert_eq!(amplitude_to_dbfs(0.0_f32), f32::NEG_INFINITY);
+        assert!((dbfs_to_amplitude(-20.0_f64) - 0.1).abs() < 1e-15);
+        for i in -100..=20 {
+            let dbfs = f64::from(i) * 1.5;
+            assert!((amplitude_to_dbfs(dbfs_to_amplitude(dbfs)) - dbfs).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_attenuation_matches_frequency_response() {
+        let filter = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        for frequency in [100.0, 1000.0, 5000.0, 15000.0] {
+            let before = sine_wave_samples(frequency, 44100.0);
+            let mut after = before.clone();
+            filter.clone().process_in_place(&mut after);
+
+            // skip the transient
+            let attenuation = attenuation_db(&before[4410..], &after[4410..]);
+            let expected = -amplitude_to_dbfs(filter.magnitude_at(frequency));
+            assert!(
+                (attenuation - expected).abs() < 0.01,
+                "{frequency} Hz: {attenuation} dB != {expected} dB"
+            );
+        }
+        assert_eq!(attenuation_db(&[0.5_f32], &[0.5]), 0.0);
+    }
+
+    #[test]
+    fn test_attenuation_of_silence() {
+        assert_eq!(attenuation_db(&[0.0_f32; 4], &[0.0; 4]), 0.0);
+        assert_eq!(attenuation_db::<f64>(&[], &[]), 0.0);
+        assert_eq!(attenuation_db(&[0.5_f32], &[0.0]), f32::INFINITY);
+        assert_eq!(attenuation_db(&[0.0_f32], &[0.5]), f32::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`BandpassFilter`] and [`BandstopFilter`].
+
+use crate::{Filter, FilterError, HighpassFilter, LowpassFilter, Sample};
+
+/// A band-pass filter that only keeps the frequencies between a lower and an
+/// upper cutoff frequency.
+///
+/// It is built from a first-order [`HighpassFilter`] at the lower cutoff
+/// frequency followed by a first-order [`LowpassFilter`] at the upper cutoff
+/// frequency. Hence, both edges of the band roll off with 6 dB/octave.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+#[derive(Debug, Clone)]
+pub struct BandpassFilter<T> {
+    highpass: HighpassFilter<T>,
+    lowpass: LowpassFilter<T>,
+}
+
+/// A band-stop filter that removes the frequencies between a lower and an
+/// upper cutoff frequency.
+///
+/// It is built from a first-order [`LowpassFilter`] at the lower cutoff
+/// frequency and a first-order [`HighpassFilter`] at the upper cutoff
+/// frequency, whose outputs are summed up.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+#[derive(Debug, Clone)]
+pub struct BandstopFilter<T> {
+    lowpass: LowpassFilter<T>,
+    highpass: HighpassFilter<T>,
+}
+
+/// Checks the parameters of a band. Both cutoff frequencies are validated like
+/// the cutoff frequency of a single filter and the band must not be empty.
+fn check_band(sample_rate_hz: f64, low_hz: f64, high_hz: f64) -> Result<(), FilterError> {
+    FilterError::check_frequencies(sample_rate_hz, low_hz)?;
+    FilterError::check_frequencies(sample_rate_hz, high_hz)?;
+    if low_hz < high_hz {
+        Ok(())
+    } else {
+        Err(FilterError::InvalidBand)
+    }
+}
+
+impl<T: Sample> BandpassFilter<T> {
+    /// Create a new band-pass filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `low_hz`: Lower cutoff frequency in Hz (e.g., 60.0).
+    /// - `high_hz`: Upper cutoff frequency in Hz (e.g., 250.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, low_hz: T, high_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, low_hz, high_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid band-pass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new band-pass filter or return an error if the
+    /// parameters are invalid.
+    ///
+    /// Both cutoff frequencies are validated like in
+    /// [`LowpassFilter::try_new`]. Additionally, `low_hz` must be
+    /// smaller than `high_hz`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `low_hz`: Lower cutoff frequency in Hz (e.g., 60.0).
+    /// - `high_hz`: Upper cutoff frequency in Hz (e.g., 250.0).
+    pub fn try_new(sample_rate_hz: T, low_hz: T, high_hz: T) -> Result<Self, FilterError> {
+        check_band(sample_rate_hz.to_f64(), low_hz.to_f64(), high_hz.to_f64())?;
+        Ok(Self {
+            highpass: HighpassFilter::try_new(sample_rate_hz, low_hz)?,
+            lowpass: LowpassFilter::try_new(sample_rate_hz, high_hz)?,
+        })
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// It is mandatory to operate on values in range `-1.0..=1.0`,
+    /// which is also the default in DSP. The returned value is also
+    /// guaranteed to be in that range.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        self.lowpass.run(self.highpass.run(input))
+    }
+
+    /// Reset the internal filter state.
+    pub const fn reset(&mut self) {
+        self.highpass.reset();
+        self.lowpass.reset();
+    }
+}
+
+impl<T: Sample> Filter<T> for BandpassFilter<T> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+impl<T: Sample> BandstopFilter<T> {
+    /// Create a new band-stop filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `low_hz`: Lower cutoff frequency in Hz (e.g., 60.0).
+    /// - `high_hz`: Upper cutoff frequency in Hz (e.g., 250.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, low_hz: T, high_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, low_hz, high_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid band-stop filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new band-stop filter or return an error if the
+    /// parameters are invalid.
+    ///
+    /// Both cutoff frequencies are validated like in
+    /// [`LowpassFilter::try_new`]. Additionally, `low_hz` must be
+    /// smaller than `high_hz`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `low_hz`: Lower cutoff frequency in Hz (e.g., 60.0).
+    /// - `high_hz`: Upper cutoff frequency in Hz (e.g., 250.0).
+    pub fn try_new(sample_rate_hz: T, low_hz: T, high_hz: T) -> Result<Self, FilterError> {
+        check_band(sample_rate_hz.to_f64(), low_hz.to_f64(), high_hz.to_f64())?;
+        Ok(Self {
+            lowpass: LowpassFilter::try_new(sample_rate_hz, low_hz)?,
+            highpass: HighpassFilter::try_new(sample_rate_hz, high_hz)?,
+        })
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// It is mandatory to operate on values in range `-1.0..=1.0`,
+    /// which is also the default in DSP. The returned value is also
+    /// guaranteed to be in that range.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        let value = self.lowpass.run(input) + self.highpass.run(input);
+        value.clamp(-T::ONE, T::ONE)
+    }
+
+    /// Reset the internal filter state.
+    pub const fn reset(&mut self) {
+        self.lowpass.reset();
+        self.highpass.reset();
+    }
+}
+
+impl<T: Sample> Filter<T> for BandstopFilter<T> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::power_ratio;
+
+    #[test]
+    fn test_bandpass_and_bandstop() {
+        let mut bandpass = BandpassFilter::<f64>::new(44100.0, 60.0, 250.0);
+        let mut bandstop = BandstopFilter::<f64>::new(44100.0, 60.0, 250.0);
+
+        let pass_low = power_ratio(10.0, &mut bandpass);
+        let pass_mid = power_ratio(120.0, &mut bandpass);
+        let pass_high = power_ratio(5000.0, &mut bandpass);
+        assert!(pass_mid > 3.0 * pass_low);
+        assert!(pass_mid > 3.0 * pass_high);
+
+        let stop_low = power_ratio(10.0, &mut bandstop);
+        let stop_mid = power_ratio(120.0, &mut bandstop);
+        let stop_high = power_ratio(5000.0, &mut bandstop);
+        assert!(stop_low > 1.5 * stop_mid);
+        assert!(stop_high > 1.5 * stop_mid);
+    }
+
+    #[test]
+    fn test_band_validation() {
+        assert_eq!(
+            BandpassFilter::<f32>::try_new(44100.0, 250.0, 60.0).unwrap_err(),
+            FilterError::InvalidBand
+        );
+        assert_eq!(
+            BandstopFilter::<f64>::try_new(44100.0, 60.0, 60.0).unwrap_err(),
+            FilterError::InvalidBand
+        );
+        assert_eq!(
+            BandstopFilter::<f64>::try_new(44100.0, 60.0, 30000.0).unwrap_err(),
+            FilterError::NyquistViolation
+        );
+    }
+}
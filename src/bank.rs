@@ -0,0 +1,329 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`LowpassFilterBank`] and its optional SIMD backend.
+
+use crate::{Filter, FilterError, InitialState, LowpassFilter, Sample};
+
+/// A sample type that [`LowpassFilterBank`] can process in SIMD lanes.
+///
+/// It is implemented for `f32` and `f64`. With the `simd` feature, the
+/// lanes are processed with the vector types of the `wide` crate (`f32x8`
+/// and `f64x4`). Without it, or for the remaining channels that don't fill a
+/// whole vector, a scalar fallback is used. Both produce the same results.
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait SimdSample: Sample + private::Sealed {}
+
+mod private {
+    pub trait Sealed: Sized {
+        /// Filters one frame in-place and updates the state of each
+        /// channel.
+        fn run_lanes(prev: &mut [Self], frame: &mut [Self], alpha: Self, first: bool);
+    }
+}
+
+/// The scalar fallback. Performs exactly the same operations as
+/// [`LowpassFilter::run`].
+#[inline]
+fn run_lanes_scalar<T: Sample>(prev: &mut [T], frame: &mut [T], alpha: T, first: bool) {
+    for (prev, sample) in prev.iter_mut().zip(frame.iter_mut()) {
+        if first {
+            *prev = *sample;
+        } else {
+            *prev = *prev + alpha * (*sample - *prev);
+        }
+        *sample = prev.clamp(-T::ONE, T::ONE);
+    }
+}
+
+macro_rules! impl_simd_sample {
+    ($t:ty, $vector:ident, $lanes:literal) => {
+        impl SimdSample for $t {}
+
+        impl private::Sealed for $t {
+            #[cfg(not(feature = "simd"))]
+            #[inline]
+            fn run_lanes(prev: &mut [Self], frame: &mut [Self], alpha: Self, first: bool) {
+                run_lanes_scalar(prev, frame, alpha, first);
+            }
+
+            #[cfg(feature = "simd")]
+            #[inline]
+            fn run_lanes(prev: &mut [Self], frame: &mut [Self], alpha: Self, first: bool) {
+                use wide::{CmpGt, CmpLt, $vector};
+
+                let alpha_v = $vector::splat(alpha);
+                let one = $vector::splat(1.0);
+                let minus_one = $vector::splat(-1.0);
+
+                let mut prev_chunks = prev.chunks_exact_mut($lanes);
+                let mut frame_chunks = frame.chunks_exact_mut($lanes);
+                for (prev, frame) in (&mut prev_chunks).zip(&mut frame_chunks) {
+                    let prev: &mut [Self; $lanes] = prev.try_into().unwrap();
+                    let frame: &mut [Self; $lanes] = frame.try_into().unwrap();
+                    let input = $vector::from(*frame);
+
+                    // separate multiplication and addition instead of FMA,
+                    // to match the scalar path bit by bit
+                    let value = if first {
+                        *prev = *frame;
+                        input
+                    } else {
+                        let state = $vector::from(*prev);
+                        let state = state + alpha_v * (input - state);
+                        *prev = state.to_array();
+                        state
+                    };
+
+                    // same semantics as `clamp()`: NaN stays NaN
+                    let value = value.cmp_lt(minus_one).blend(minus_one, value);
+                    let value = value.cmp_gt(one).blend(one, value);
+                    *frame = value.to_array();
+                }
+
+                run_lanes_scalar(
+                    prev_chunks.into_remainder(),
+                    frame_chunks.into_remainder(),
+                    alpha,
+                    first,
+                );
+            }
+        }
+    };
+}
+
+impl_simd_sample!(f32, f32x8, 8);
+impl_simd_sample!(f64, f64x4, 4);
+
+/// A bank of many independent lowpass filters that share the same
+/// parameters, such as for microphone arrays or the per-bin smoothing of a
+/// spectrum.
+///
+/// Each channel behaves exactly like a [`LowpassFilter`]: the results are
+/// bit-identical to calling [`LowpassFilter::run`] for every channel. With
+/// the `simd` feature, multiple channels are filtered at the same time. See
+/// [`SimdSample`] for details.
+///
+/// The [`InitialState`] can be configured like for [`LowpassFilter`]. Unlike
+/// [`crate::MultiChannelLowpassFilter`], the coefficient can't be smoothed,
+/// as all channels are processed in lockstep.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::LowpassFilterBank;
+///
+/// let mut bank = LowpassFilterBank::<f32, 64>::new(44100.0, 120.0);
+/// let frame = bank.run([0.5; 64]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LowpassFilterBank<T, const CH: usize> {
+    alpha: T,
+    prev: [T; CH],
+    next_is_first: bool,
+    initial_state: InitialState<T>,
+}
+
+impl<T: SimdSample, const CH: usize> LowpassFilterBank<T, CH> {
+    /// Create a new lowpass filter bank.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, cutoff_frequency_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter bank or return an error if the
+    /// parameters are invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`LowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        let filter = LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?;
+        Ok(Self {
+            alpha: filter.alpha,
+            prev: [T::ZERO; CH],
+            next_is_first: false,
+            initial_state: InitialState::Zero,
+        })
+    }
+
+    /// Replaces the [`InitialState`] of all channels. The state is reset
+    /// accordingly.
+    #[must_use]
+    pub const fn with_initial_state(mut self, initial_state: InitialState<T>) -> Self {
+        self.initial_state = initial_state;
+        self.reset();
+        self
+    }
+
+    /// Filter a single frame, i.e., one sample per channel, and return
+    /// the filtered frame.
+    #[inline]
+    pub fn run(&mut self, frame: [T; CH]) -> [T; CH] {
+        let mut frame = frame;
+        self.run_in_place(&mut frame);
+        frame
+    }
+
+    /// Filter a single frame in-place.
+    #[inline]
+    pub fn run_in_place(&mut self, frame: &mut [T; CH]) {
+        debug_assert!(
+            frame
+                .iter()
+                .all(|sample| (-T::ONE..=T::ONE).contains(sample)),
+            "samples must be in range -1.0..=1.0: {frame:?}"
+        );
+        T::run_lanes(&mut self.prev, frame, self.alpha, self.next_is_first);
+        self.next_is_first = false;
+    }
+
+    /// Filters interleaved frames in-place.
+    ///
+    /// # Panics
+    /// If the length of the buffer is not a multiple of the number of
+    /// channels.
+    pub fn process_interleaved(&mut self, samples: &mut [T]) {
+        assert_eq!(
+            samples.len() % CH,
+            0,
+            "the length of interleaved audio must be a multiple of the channel count ({CH})"
+        );
+        for frame in samples.chunks_exact_mut(CH) {
+            self.run_in_place(frame.try_into().unwrap());
+        }
+    }
+
+    /// Reset the internal filter state of all channels according to the
+    /// [`InitialState`].
+    pub const fn reset(&mut self) {
+        match self.initial_state {
+            InitialState::Zero => self.reset_to(T::ZERO),
+            InitialState::Value(value) => self.reset_to(value),
+            InitialState::FirstSample => {
+                self.reset_to(T::ZERO);
+                self.next_is_first = true;
+            }
+        }
+    }
+
+    /// Reset the internal filter state of all channels, so that they are
+    /// primed to the given value. See [`LowpassFilter::reset_to`].
+    pub const fn reset_to(&mut self, value: T) {
+        self.prev = [value; CH];
+        self.next_is_first = false;
+    }
+}
+
+impl<T: SimdSample, const CH: usize> Filter<[T; CH]> for LowpassFilterBank<T, CH> {
+    #[inline]
+    fn run(&mut self, input: [T; CH]) -> [T; CH] {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+
+    fn assert_bit_identical<T: SimdSample + PartialEq>(to_bits: fn(T) -> u64) {
+        const CH: usize = 13;
+        let signal = sine_wave_samples(120.0, 44100.0);
+        for initial_state in [
+            InitialState::Zero,
+            InitialState::FirstSample,
+            InitialState::Value(T::from_f64(0.25)),
+        ] {
+            let mut bank = LowpassFilterBank::<T, CH>::new(T::from_f64(44100.0), T::from_f64(90.0))
+                .with_initial_state(initial_state);
+            let mut filters: [LowpassFilter<T>; CH] = core::array::from_fn(|_| {
+                LowpassFilter::new(T::from_f64(44100.0), T::from_f64(90.0))
+                    .with_initial_state(initial_state)
+            });
+            assert_bit_identical_runs(&mut bank, &mut filters, &signal, to_bits);
+        }
+    }
+
+    fn assert_bit_identical_runs<T: SimdSample + PartialEq, const CH: usize>(
+        bank: &mut LowpassFilterBank<T, CH>,
+        filters: &mut [LowpassFilter<T>; CH],
+        signal: &[f64],
+        to_bits: fn(T) -> u64,
+    ) {
+        for _ in 0..2 {
+            for (i, sample) in signal.iter().enumerate().take(4096) {
+                let frame: [T; CH] = core::array::from_fn(|ch| {
+                    T::from_f64(
+                        0.9 * sample * (1.0 - ch as f64 / CH as f64) + (i % 2) as f64 * 0.05,
+                    )
+                });
+                let actual = bank.run(frame);
+                for ((actual, sample), filter) in actual.iter().zip(frame).zip(filters.iter_mut()) {
+                    assert_eq!(to_bits(*actual), to_bits(filter.run(sample)));
+                }
+            }
+            bank.reset();
+            filters.iter_mut().for_each(LowpassFilter::reset);
+        }
+    }
+
+    #[test]
+    fn test_bank_is_bit_identical_to_scalar_filter() {
+        assert_bit_identical::<f32>(|x| u64::from(x.to_bits()));
+        assert_bit_identical::<f64>(f64::to_bits);
+    }
+
+    #[test]
+    fn test_bank_interleaved() {
+        let mut samples = [0.5_f32; 6 * 4];
+        let mut bank = LowpassFilterBank::<f32, 6>::new(44100.0, 120.0);
+        bank.process_interleaved(&mut samples);
+        let mut filter = LowpassFilter::<f32>::new(44100.0, 120.0);
+        let expected = [0.5; 4].map(|sample| filter.run(sample));
+        for (frame, expected) in samples.chunks_exact(6).zip(expected) {
+            assert!(frame.iter().all(|sample| *sample == expected));
+        }
+    }
+}
@@ -0,0 +1,448 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! The `lowpass` command-line tool, which filters WAV files.
+//!
+//! Run `lowpass --help` for the usage. It is built with the `cli` feature:
+//! `cargo install lowpass-filter --features cli`.
+
+#![deny(
+    clippy::all,
+    clippy::cargo,
+    clippy::nursery,
+    clippy::must_use_candidate
+)]
+
+use hound::SampleFormat;
+use lowpass_filter::{
+    BiquadLowpassFilter, ButterworthLowpassFilter, ChannelMode, Filter, FilterError, LowpassFilter,
+    Unbounded, check_wav_file, filter_wav_file_with_progress,
+};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// The number of frames that are read, filtered, and written at once.
+const BLOCK_FRAMES: usize = 65536;
+
+const USAGE: &str = "\
+Filters a WAV file with a lowpass filter.
+
+Usage: lowpass [OPTIONS] --cutoff <HZ> <INPUT>
+
+Arguments:
+  <INPUT>  The WAV file to filter
+
+Options:
+  -c, --cutoff <HZ>        Cutoff frequency in Hz
+  -t, --type <TYPE>        Filter type: first-order, biquad, or butterworth
+                           [default: first-order]. biquad and butterworth
+                           only accept samples in -1.0..=1.0, so louder
+                           samples of float files are clipped
+  -n, --order <N>          Order of the butterworth filter [default: 2]
+  -p, --passes <N>         Number of times the filter is applied [default: 1]
+  -o, --output <PATH>      Output file [default: <INPUT>_lowpassed.wav]
+      --channels <MODE>    Output channels: keep, mono, or the index of a
+                           single channel (starting at 0) [default: keep]
+      --dry-run            Check the input and print what would be done
+                           without writing the output file
+  -h, --help               Print help
+  -V, --version            Print version
+
+Exit codes: 0 on success, 1 if the file could not be filtered, and 2 for
+invalid arguments.";
+
+/// The filter types that can be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterType {
+    FirstOrder,
+    Biquad,
+    Butterworth,
+}
+
+impl FilterType {
+    /// Returns `true` if the filter only accepts samples in `-1.0..=1.0`,
+    /// which float files might exceed.
+    fn clips_input(self) -> bool {
+        self != Self::FirstOrder
+    }
+
+    /// Returns the name that is used on the command line.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::FirstOrder => "first-order",
+            Self::Biquad => "biquad",
+            Self::Butterworth => "butterworth",
+        }
+    }
+}
+
+/// The options of a run.
+#[derive(Debug, Clone, PartialEq)]
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    cutoff_frequency_hz: f64,
+    filter_type: FilterType,
+    order: usize,
+    passes: usize,
+    channels: ChannelMode,
+    dry_run: bool,
+}
+
+/// What the tool was asked to do.
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Help,
+    Version,
+    Run(Options),
+}
+
+/// Parses the command-line arguments, without the name of the binary.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut input = None;
+    let mut output = None;
+    let mut cutoff_frequency_hz = None;
+    let mut filter_type = FilterType::FirstOrder;
+    let mut order = None;
+    let mut passes = 1;
+    let mut channels = ChannelMode::Keep;
+    let mut dry_run = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // support both `--option value` and `--option=value`
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for {name}"))
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-c" | "--cutoff" => cutoff_frequency_hz = Some(parse_number(&name, &value()?)?),
+            "-t" | "--type" => {
+                let value = value()?;
+                filter_type = [
+                    FilterType::FirstOrder,
+                    FilterType::Biquad,
+                    FilterType::Butterworth,
+                ]
+                .into_iter()
+                .find(|filter_type| filter_type.name() == value)
+                .ok_or_else(|| format!("unknown filter type: {value}"))?;
+            }
+            "-n" | "--order" => order = Some(parse_number(&name, &value()?)?),
+            "-p" | "--passes" => passes = parse_number(&name, &value()?)?,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "--channels" => {
+                channels = match value()?.as_str() {
+                    "keep" => ChannelMode::Keep,
+                    "mono" => ChannelMode::Mono,
+                    index => ChannelMode::Select(parse_number(&name, index)?),
+                };
+            }
+            "--dry-run" => dry_run = true,
+            _ if name.starts_with('-') && name.len() > 1 => {
+                return Err(format!("unknown option: {name}"));
+            }
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    let input = input.ok_or("missing input file")?;
+    let cutoff_frequency_hz = cutoff_frequency_hz.ok_or("missing --cutoff")?;
+    if order.is_some() && filter_type != FilterType::Butterworth {
+        return Err("--order requires --type butterworth".to_string());
+    }
+    if passes == 0 {
+        return Err("--passes must be at least 1".to_string());
+    }
+    let output = output.unwrap_or_else(|| {
+        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+        input.with_file_name(format!("{stem}_lowpassed.wav"))
+    });
+
+    Ok(Command::Run(Options {
+        input,
+        output,
+        cutoff_frequency_hz,
+        filter_type,
+        order: order.unwrap_or(2),
+        passes,
+        channels,
+        dry_run,
+    }))
+}
+
+/// Parses the value of an option.
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {name}: {value}"))
+}
+
+/// The filter of one channel: one filter per pass, applied in series.
+struct Cascade {
+    filters: Vec<Box<dyn Filter<f64>>>,
+    /// See [`FilterType::clips_input`].
+    clip_input: bool,
+}
+
+impl Cascade {
+    fn new(options: &Options, sample_rate_hz: f64) -> Result<Self, FilterError> {
+        let cutoff_frequency_hz = options.cutoff_frequency_hz;
+        let filters = (0..options.passes)
+            .map(|_| -> Result<Box<dyn Filter<f64>>, FilterError> {
+                Ok(match options.filter_type {
+                    FilterType::FirstOrder => Box::new(
+                        LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?
+                            .with_range_policy(Unbounded),
+                    ),
+                    FilterType::Biquad => Box::new(BiquadLowpassFilter::try_new(
+                        sample_rate_hz,
+                        cutoff_frequency_hz,
+                    )?),
+                    FilterType::Butterworth => Box::new(ButterworthLowpassFilter::try_new(
+                        sample_rate_hz,
+                        cutoff_frequency_hz,
+                        options.order,
+                    )?),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            filters,
+            clip_input: options.filter_type.clips_input(),
+        })
+    }
+}
+
+impl Filter<f64> for Cascade {
+    fn run(&mut self, input: f64) -> f64 {
+        let input = if self.clip_input {
+            input.clamp(-1.0, 1.0)
+        } else {
+            input
+        };
+        self.filters
+            .iter_mut()
+            .fold(input, |sample, filter| filter.run(sample))
+    }
+
+    fn reset(&mut self) {
+        self.filters.iter_mut().for_each(|filter| filter.reset());
+    }
+}
+
+/// Filters the file, or only checks it for a dry run.
+fn run(options: &Options) -> Result<(), String> {
+    let create_filter = |sample_rate_hz| Cascade::new(options, sample_rate_hz);
+    // also rejects an output path that refers to the input file
+    let spec = check_wav_file(
+        &options.input,
+        &options.output,
+        options.channels,
+        create_filter,
+    )
+    .map_err(|e| format!("{}: {e}", options.input.display()))?;
+
+    if spec.sample_format == SampleFormat::Float && options.filter_type.clips_input() {
+        eprintln!(
+            "lowpass: warning: the {} filter clips float samples outside of -1.0..=1.0",
+            options.filter_type.name()
+        );
+    }
+
+    let summary = format!(
+        "{} -> {} ({} channel(s), {} Hz, {}-bit {:?}; {} filter, cutoff {} Hz, {} pass(es))",
+        options.input.display(),
+        options.output.display(),
+        spec.channels,
+        spec.sample_rate,
+        spec.bits_per_sample,
+        spec.sample_format,
+        options.filter_type.name(),
+        options.cutoff_frequency_hz,
+        options.passes,
+    );
+    if options.dry_run {
+        println!("dry run: {summary}");
+        return Ok(());
+    }
+
+    // long recordings take a while, so show the progress on a terminal
+    let show_progress = std::io::stderr().is_terminal();
+    filter_wav_file_with_progress(
+        &options.input,
+        &options.output,
+        options.channels,
+        BLOCK_FRAMES,
+        create_filter,
+        |progress| {
+            if show_progress {
+                eprint!("\r{:5.1}%", progress.fraction() * 100.0);
+            }
+        },
+    )
+    .map_err(|e| format!("{}: {e}", options.input.display()))?;
+    if show_progress {
+        eprint!("\r");
+    }
+    println!("{summary}");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Version) => {
+            println!("lowpass {}", env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Run(options)) => match run(&options) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("lowpass: error: {e}");
+                ExitCode::from(1)
+            }
+        },
+        Err(e) => {
+            eprintln!("lowpass: error: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| (*arg).to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse(&["in.wav", "--cutoff", "100"]),
+            Ok(Command::Run(Options {
+                input: PathBuf::from("in.wav"),
+                output: PathBuf::from("in_lowpassed.wav"),
+                cutoff_frequency_hz: 100.0,
+                filter_type: FilterType::FirstOrder,
+                order: 2,
+                passes: 1,
+                channels: ChannelMode::Keep,
+                dry_run: false,
+            }))
+        );
+        assert_eq!(
+            parse(&[
+                "--cutoff=250.5",
+                "-t",
+                "butterworth",
+                "-n",
+                "4",
+                "--passes=3",
+                "-o",
+                "out.wav",
+                "--channels",
+                "1",
+                "--dry-run",
+                "dir/in.wav",
+            ]),
+            Ok(Command::Run(Options {
+                input: PathBuf::from("dir/in.wav"),
+                output: PathBuf::from("out.wav"),
+                cutoff_frequency_hz: 250.5,
+                filter_type: FilterType::Butterworth,
+                order: 4,
+                passes: 3,
+                channels: ChannelMode::Select(1),
+                dry_run: true,
+            }))
+        );
+        assert_eq!(parse(&["in.wav", "--help"]), Ok(Command::Help));
+        assert_eq!(parse(&["-V"]), Ok(Command::Version));
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse(&["in.wav"]).is_err());
+        assert!(parse(&["--cutoff", "100"]).is_err());
+        assert!(parse(&["in.wav", "--cutoff"]).is_err());
+        assert!(parse(&["in.wav", "--cutoff", "abc"]).is_err());
+        assert!(parse(&["in.wav", "-c", "100", "--order", "4"]).is_err());
+        assert!(parse(&["in.wav", "-c", "100", "--passes", "0"]).is_err());
+        assert!(parse(&["in.wav", "-c", "100", "--channels", "left"]).is_err());
+        assert!(parse(&["in.wav", "other.wav", "-c", "100"]).is_err());
+        assert!(parse(&["in.wav", "-c", "100", "--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_run_rejects_input_as_output() {
+        let dir = std::env::temp_dir().join("lowpass-cli-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("in.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+        for sample in [0_i16, 1000, -1000, 2000] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        let before = std::fs::read(&input).unwrap();
+
+        let dir_name = dir.file_name().unwrap().to_str().unwrap();
+        for output in ["./in.wav".to_string(), format!("../{dir_name}/in.wav")] {
+            let Ok(Command::Run(mut options)) = parse(&[
+                input.to_str().unwrap(),
+                "-c",
+                "100",
+                "-o",
+                dir.join(&output).to_str().unwrap(),
+            ]) else {
+                panic!("{output} must be accepted by the parser");
+            };
+            assert!(run(&options).is_err(), "{output}");
+            options.dry_run = true;
+            assert!(run(&options).is_err(), "{output}");
+        }
+        assert_eq!(std::fs::read(&input).unwrap(), before);
+    }
+}
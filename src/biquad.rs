@@ -0,0 +1,311 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`BiquadLowpassFilter`] and the second-order sections it is
+//! built from.
+
+use crate::{Filter, FilterError, Sample, math};
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// Normalized coefficients `[b0, b1, b2, a1, a2]` of a second-order section.
+pub(crate) type Coefficients = [f64; 5];
+
+/// Calculates the coefficients of a second-order lowpass according to the
+/// [Audio EQ Cookbook](https://www.w3.org/TR/audio-eq-cookbook/) by Robert
+/// Bristow-Johnson.
+///
+/// The parameters must already be validated.
+pub(crate) fn lowpass_coefficients(
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+    q: f64,
+) -> Coefficients {
+    let w0 = 2.0 * PI * cutoff_frequency_hz / sample_rate_hz;
+    let (sin_w0, cos_w0) = math::sin_cos(w0);
+    let alpha = sin_w0 / (2.0 * q);
+
+    let a0 = 1.0 + alpha;
+    let b1 = (1.0 - cos_w0) / a0;
+    let b0 = b1 / 2.0;
+    let a1 = -2.0 * cos_w0 / a0;
+    let a2 = (1.0 - alpha) / a0;
+    [b0, b1, b0, a1, a2]
+}
+
+/// Calculates the coefficients of a first-order lowpass that is designed with
+/// the bilinear transform. Unlike [`crate::LowpassFilter`], the attenuation is
+/// exactly -3 dB at the cutoff frequency. `b2` and `a2` are zero.
+///
+/// The parameters must already be validated.
+pub(crate) fn first_order_lowpass_coefficients(
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) -> Coefficients {
+    let (sin, cos) = math::sin_cos(PI * cutoff_frequency_hz / sample_rate_hz);
+    let k = sin / cos;
+
+    let b0 = k / (1.0 + k);
+    let a1 = (k - 1.0) / (k + 1.0);
+    [b0, b0, 0.0, a1, 0.0]
+}
+
+/// Checks the frequencies of a filter that is designed with the bilinear
+/// transform. In contrast to the first-order filters, the cutoff frequency
+/// must be strictly below the Nyquist frequency, as the filter would be
+/// unstable otherwise.
+pub(crate) fn check_frequencies(
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) -> Result<(), FilterError> {
+    FilterError::check_frequencies(sample_rate_hz, cutoff_frequency_hz)?;
+    if cutoff_frequency_hz * 2.0 >= sample_rate_hz {
+        Err(FilterError::NyquistViolation)
+    } else {
+        Ok(())
+    }
+}
+
+/// A second-order IIR section in transposed direct form II.
+///
+/// This is the building block of the higher-order filters. It doesn't check
+/// or clamp the values it processes.
+#[derive(Debug, Clone)]
+pub(crate) struct Biquad<T> {
+    b0: T,
+    b1: T,
+    b2: T,
+    a1: T,
+    a2: T,
+    s1: T,
+    s2: T,
+}
+
+impl<T: Sample> Biquad<T> {
+    /// Creates a new section from the normalized coefficients.
+    pub(crate) fn new(coefficients: Coefficients) -> Self {
+        let [b0, b1, b2, a1, a2] = coefficients;
+        Self {
+            b0: T::from_f64(b0),
+            b1: T::from_f64(b1),
+            b2: T::from_f64(b2),
+            a1: T::from_f64(a1),
+            a2: T::from_f64(a2),
+            s1: T::ZERO,
+            s2: T::ZERO,
+        }
+    }
+
+    /// Filters a single sample.
+    #[inline]
+    pub(crate) fn run(&mut self, input: T) -> T {
+        let output = self.b0 * input + self.s1;
+        self.s1 = self.b1 * input - self.a1 * output + self.s2;
+        self.s2 = self.b2 * input - self.a2 * output;
+        output
+    }
+
+    /// Resets the internal state.
+    pub(crate) const fn reset(&mut self) {
+        self.s1 = T::ZERO;
+        self.s2 = T::ZERO;
+    }
+}
+
+/// A second-order (biquad) lowpass filter that consumes and emits items one
+/// by one.
+///
+/// In contrast to [`crate::LowpassFilter`], the filter rolls off with
+/// 12 dB/octave above the cutoff frequency. The coefficients are calculated
+/// according to the
+/// [Audio EQ Cookbook](https://www.w3.org/TR/audio-eq-cookbook/).
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::{BiquadLowpassFilter, apply_filter};
+///
+/// let mut samples = [0.0, 0.5, 1.0, -0.25];
+/// let mut filter = BiquadLowpassFilter::<f32>::new(44100.0, 120.0);
+/// apply_filter(&mut filter, &mut samples);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BiquadLowpassFilter<T> {
+    section: Biquad<T>,
+}
+
+impl<T: Sample> BiquadLowpassFilter<T> {
+    /// Create a new second-order lowpass filter with a Butterworth
+    /// quality factor of `1/sqrt(2)`, i.e., a maximally flat passband.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        Self::with_q(
+            sample_rate_hz,
+            cutoff_frequency_hz,
+            T::from_f64(FRAC_1_SQRT_2),
+        )
+    }
+
+    /// Create a new second-order lowpass filter with a Butterworth
+    /// quality factor or return an error if the parameters are
+    /// invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`crate::LowpassFilter::try_new`], except that the cutoff
+    /// frequency must be strictly below the Nyquist frequency.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        Self::try_with_q(
+            sample_rate_hz,
+            cutoff_frequency_hz,
+            T::from_f64(FRAC_1_SQRT_2),
+        )
+    }
+
+    /// Create a new second-order lowpass filter with a custom
+    /// quality factor.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    /// - `q`: Quality factor (e.g., `0.7071` for Butterworth). Higher
+    ///   values lead to a resonance peak at the cutoff frequency.
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_with_q`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn with_q(sample_rate_hz: T, cutoff_frequency_hz: T, q: T) -> Self {
+        match Self::try_with_q(sample_rate_hz, cutoff_frequency_hz, q) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid biquad lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new second-order lowpass filter with a custom
+    /// quality factor or return an error if the parameters are
+    /// invalid.
+    ///
+    /// The quality factor must be finite and greater than zero.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    /// - `q`: Quality factor (e.g., `0.7071` for Butterworth).
+    pub fn try_with_q(
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+        q: T,
+    ) -> Result<Self, FilterError> {
+        let sample_rate_hz = sample_rate_hz.to_f64();
+        let cutoff_frequency_hz = cutoff_frequency_hz.to_f64();
+        let q = q.to_f64();
+        check_frequencies(sample_rate_hz, cutoff_frequency_hz)?;
+        if !q.is_finite() || q <= 0.0 {
+            return Err(FilterError::InvalidQualityFactor);
+        }
+
+        let coefficients = lowpass_coefficients(sample_rate_hz, cutoff_frequency_hz, q);
+        Ok(Self {
+            section: Biquad::new(coefficients),
+        })
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// It is mandatory to operate on values in range `-1.0..=1.0`,
+    /// which is also the default in DSP. The returned value is
+    /// clamped to that range, as the filter slightly overshoots on
+    /// steep edges.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        debug_assert!(
+            (-T::ONE..=T::ONE).contains(&input),
+            "samples must be in range -1.0..=1.0: {input:?}"
+        );
+
+        self.section.run(input).clamp(-T::ONE, T::ONE)
+    }
+
+    /// Reset the internal filter state.
+    pub const fn reset(&mut self) {
+        self.section.reset();
+    }
+}
+
+impl<T: Sample> Filter<T> for BiquadLowpassFilter<T> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LowpassFilter;
+    use crate::test_util::power_ratio;
+
+    #[test]
+    fn test_biquad_lowpass() {
+        let mut biquad = BiquadLowpassFilter::<f64>::new(44100.0, 500.0);
+        let mut first_order = LowpassFilter::<f64>::new(44100.0, 500.0);
+
+        // -3 dB at the cutoff frequency
+        let at_cutoff = power_ratio(500.0, &mut biquad);
+        assert!((at_cutoff - 0.5).abs() < 0.01, "{at_cutoff}");
+
+        // steeper than the first-order filter
+        let biquad_stop = power_ratio(4000.0, &mut biquad);
+        let first_order_stop = power_ratio(4000.0, &mut first_order);
+        assert!(biquad_stop * 5.0 < first_order_stop);
+    }
+
+    #[test]
+    fn test_biquad_validation() {
+        assert_eq!(
+            BiquadLowpassFilter::<f32>::try_new(44100.0, 22050.0).unwrap_err(),
+            FilterError::NyquistViolation
+        );
+        assert_eq!(
+            BiquadLowpassFilter::<f64>::try_with_q(44100.0, 1000.0, 0.0).unwrap_err(),
+            FilterError::InvalidQualityFactor
+        );
+    }
+}
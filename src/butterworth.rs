@@ -0,0 +1,216 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`ButterworthLowpassFilter`].
+
+use crate::biquad::{self, Biquad};
+use crate::{Filter, FilterError, Sample};
+use core::f64::consts::PI;
+
+/// A Butterworth lowpass filter of selectable order that consumes and emits
+/// items one by one.
+///
+/// The filter rolls off with `6 * order` dB/octave above the cutoff frequency
+/// and has a maximally flat passband. It is built as a cascade of second-order
+/// sections (plus one first-order section for odd orders) that are designed
+/// with the bilinear transform. Hence, the attenuation is exactly -3 dB at the
+/// cutoff frequency, independent of the order. This is not the case when the
+/// same first-order filter is applied multiple times.
+///
+/// The order is selected at runtime, but all sections are stored inline, so
+/// the filter doesn't need an allocator.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::{ButterworthLowpassFilter, apply_filter};
+///
+/// let mut samples = [0.0, 0.5, 1.0, -0.25];
+/// // 24 dB/octave
+/// let mut filter = ButterworthLowpassFilter::<f32>::new(44100.0, 80.0, 4);
+/// apply_filter(&mut filter, &mut samples);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ButterworthLowpassFilter<T> {
+    sections: [Biquad<T>; ButterworthLowpassFilter::<()>::MAX_ORDER.div_ceil(2)],
+    order: usize,
+}
+
+impl<T> ButterworthLowpassFilter<T> {
+    /// The maximum supported order.
+    pub const MAX_ORDER: usize = 16;
+
+    /// Returns the order of the filter.
+    #[must_use]
+    pub const fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the number of sections that are actually in use.
+    const fn section_count(&self) -> usize {
+        self.order.div_ceil(2)
+    }
+}
+
+impl<T: Sample> ButterworthLowpassFilter<T> {
+    /// Create a new Butterworth lowpass filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    /// - `order`: Order of the filter, from 1 up to and including
+    ///   [`Self::MAX_ORDER`].
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T, order: usize) -> Self {
+        match Self::try_new(sample_rate_hz, cutoff_frequency_hz, order) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid Butterworth lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new Butterworth lowpass filter or return an error if
+    /// the parameters are invalid.
+    ///
+    /// The frequencies are validated the same way as in
+    /// [`crate::BiquadLowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    /// - `order`: Order of the filter, from 1 up to and including
+    ///   [`Self::MAX_ORDER`].
+    pub fn try_new(
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+        order: usize,
+    ) -> Result<Self, FilterError> {
+        let sample_rate_hz = sample_rate_hz.to_f64();
+        let cutoff_frequency_hz = cutoff_frequency_hz.to_f64();
+        biquad::check_frequencies(sample_rate_hz, cutoff_frequency_hz)?;
+        if !(1..=Self::MAX_ORDER).contains(&order) {
+            return Err(FilterError::InvalidOrder);
+        }
+
+        let sections = core::array::from_fn(|i| {
+            let coefficients = if i < order / 2 {
+                // Pole pairs of the analog prototype.
+                let theta = (2 * i + 1) as f64 * PI / (2 * order) as f64;
+                let q = 1.0 / (2.0 * crate::math::sin_cos(theta).0);
+                biquad::lowpass_coefficients(sample_rate_hz, cutoff_frequency_hz, q)
+            } else if i == order / 2 && order % 2 == 1 {
+                // Real pole of odd orders.
+                biquad::first_order_lowpass_coefficients(sample_rate_hz, cutoff_frequency_hz)
+            } else {
+                // Unused; identity.
+                [1.0, 0.0, 0.0, 0.0, 0.0]
+            };
+            Biquad::new(coefficients)
+        });
+
+        Ok(Self { sections, order })
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// It is mandatory to operate on values in range `-1.0..=1.0`,
+    /// which is also the default in DSP. The returned value is
+    /// clamped to that range, as the filter slightly overshoots on
+    /// steep edges.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        debug_assert!(
+            (-T::ONE..=T::ONE).contains(&input),
+            "samples must be in range -1.0..=1.0: {input:?}"
+        );
+
+        let count = self.section_count();
+        self.sections[..count]
+            .iter_mut()
+            .fold(input, |value, section| section.run(value))
+            .clamp(-T::ONE, T::ONE)
+    }
+
+    /// Reset the internal filter state.
+    pub fn reset(&mut self) {
+        self.sections.iter_mut().for_each(Biquad::reset);
+    }
+}
+
+impl<T: Sample> Filter<T> for ButterworthLowpassFilter<T> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::power_ratio;
+
+    #[test]
+    fn test_butterworth_keeps_cutoff_and_slope() {
+        for order in 1..=ButterworthLowpassFilter::<f64>::MAX_ORDER {
+            let mut filter = ButterworthLowpassFilter::<f64>::new(44100.0, 500.0, order);
+
+            // -3 dB at the cutoff frequency, independent of the order
+            let at_cutoff = power_ratio(500.0, &mut filter);
+            assert!((at_cutoff - 0.5).abs() < 0.01, "order={order}: {at_cutoff}");
+
+            // roughly -6 dB per order one octave above the cutoff frequency
+            let octave = power_ratio(1000.0, &mut filter);
+            let octave_db = 10.0 * octave.log10();
+            let expected_db = -6.0 * order as f64;
+            assert!(
+                (octave_db - expected_db).abs() < 1.5,
+                "order={order}: {octave_db} dB"
+            );
+        }
+    }
+
+    #[test]
+    fn test_butterworth_validation() {
+        assert_eq!(
+            ButterworthLowpassFilter::<f32>::try_new(44100.0, 100.0, 0).unwrap_err(),
+            FilterError::InvalidOrder
+        );
+        assert_eq!(
+            ButterworthLowpassFilter::<f64>::try_new(44100.0, 100.0, 17).unwrap_err(),
+            FilterError::InvalidOrder
+        );
+        assert_eq!(
+            ButterworthLowpassFilter::<f64>::try_new(44100.0, 22050.0, 2).unwrap_err(),
+            FilterError::NyquistViolation
+        );
+    }
+}
@@ -0,0 +1,337 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the alternative constructors of [`LowpassFilter`], which take
+//! a time constant, a rise time, a half-life, an EMA period, or the
+//! coefficient itself instead of a cutoff frequency.
+//!
+//! All of them describe the same filter. They are converted to the
+//! coefficient `alpha` and the equivalent cutoff frequency
+//! `alpha * sample_rate / (2π * (1 - alpha))`, which is kept when the sample
+//! rate changes.
+
+use crate::{FilterError, LowpassFilter, RangePolicy, Sample, math};
+use core::f64::consts::{LN_2, PI};
+
+/// `ln(9)`: a first-order filter rises from 10% to 90% in `ln(9) * τ`.
+const LN_9: f64 = 2.197_224_577_336_219_6;
+
+impl<T: Sample> LowpassFilter<T> {
+    /// Create a new lowpass filter from its coefficient `alpha`, as in
+    /// `y[n] = y[n-1] + alpha * (x[n] - y[n-1])`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `alpha`: The coefficient (e.g., 0.1).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_from_alpha`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn from_alpha(sample_rate_hz: T, alpha: T) -> Self {
+        match Self::try_from_alpha(sample_rate_hz, alpha) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter from its coefficient `alpha` or return an
+    /// error if the parameters are invalid.
+    ///
+    /// `alpha` must be greater than zero and smaller than one. The
+    /// equivalent cutoff frequency is only reported, so it may be above the
+    /// Nyquist frequency for `alpha` above `π / (1 + π) ≈ 0.7585`. The
+    /// sample rate of such a filter can only be raised, see
+    /// [`Self::try_set_sample_rate`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `alpha`: The coefficient (e.g., 0.1).
+    pub fn try_from_alpha(sample_rate_hz: T, alpha: T) -> Result<Self, FilterError> {
+        FilterError::check_sample_rate(sample_rate_hz.to_f64())?;
+        let alpha_f64 = alpha.to_f64();
+        if !(alpha_f64 > 0.0 && alpha_f64 < 1.0) {
+            return Err(FilterError::InvalidAlpha);
+        }
+
+        // The cutoff frequency is only the equivalent of the exact `alpha`,
+        // so it isn't checked against the Nyquist frequency.
+        let cutoff_frequency_hz =
+            alpha_f64 * sample_rate_hz.to_f64() / (2.0 * PI * (1.0 - alpha_f64));
+        Ok(Self::from_validated(
+            sample_rate_hz,
+            T::from_f64(cutoff_frequency_hz),
+            alpha,
+        ))
+    }
+
+    /// Create a new lowpass filter from the time constant `τ` of the
+    /// equivalent RC circuit, i.e., the time until the step response reaches
+    /// `1 - 1/e ≈ 63%`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `time_constant_s`: Time constant in seconds (e.g., 0.01).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_from_time_constant`]
+    /// for the fallible variant.
+    #[must_use]
+    pub fn from_time_constant(sample_rate_hz: T, time_constant_s: T) -> Self {
+        match Self::try_from_time_constant(sample_rate_hz, time_constant_s) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter from the time constant `τ` or return an
+    /// error if the parameters are invalid.
+    ///
+    /// The time constant must be finite and greater than zero. The
+    /// equivalent cutoff frequency is `1 / (2π * τ)`, see
+    /// [`Self::try_from_alpha`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `time_constant_s`: Time constant in seconds (e.g., 0.01).
+    pub fn try_from_time_constant(
+        sample_rate_hz: T,
+        time_constant_s: T,
+    ) -> Result<Self, FilterError> {
+        FilterError::check_sample_rate(sample_rate_hz.to_f64())?;
+        let time_constant_s = check_time_constant(time_constant_s)?;
+
+        let dt = 1.0 / sample_rate_hz.to_f64();
+        let alpha = dt / (time_constant_s + dt);
+        Self::try_from_alpha(sample_rate_hz, T::from_f64(alpha))
+    }
+
+    /// Create a new lowpass filter from the 10% to 90% rise time of its step
+    /// response, which is `ln(9) * τ ≈ 2.2 * τ`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `rise_time_s`: Rise time in seconds (e.g., 0.02).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_from_rise_time`] for
+    /// the fallible variant.
+    #[must_use]
+    pub fn from_rise_time(sample_rate_hz: T, rise_time_s: T) -> Self {
+        match Self::try_from_rise_time(sample_rate_hz, rise_time_s) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter from the 10% to 90% rise time of its step
+    /// response or return an error if the parameters are invalid.
+    ///
+    /// See [`Self::try_from_time_constant`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `rise_time_s`: Rise time in seconds (e.g., 0.02).
+    pub fn try_from_rise_time(sample_rate_hz: T, rise_time_s: T) -> Result<Self, FilterError> {
+        let rise_time_s = check_time_constant(rise_time_s)?;
+        Self::try_from_time_constant(sample_rate_hz, T::from_f64(rise_time_s / LN_9))
+    }
+
+    /// Create a new lowpass filter from its half-life in samples, i.e., the
+    /// number of samples after which the distance between the output and a
+    /// constant input has halved. Fractional values are allowed.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `half_life_samples`: Half-life in samples (e.g., 100.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_from_half_life_samples`]
+    /// for the fallible variant.
+    #[must_use]
+    pub fn from_half_life_samples(sample_rate_hz: T, half_life_samples: T) -> Self {
+        match Self::try_from_half_life_samples(sample_rate_hz, half_life_samples) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter from its half-life in samples or return
+    /// an error if the parameters are invalid.
+    ///
+    /// The half-life must be finite and greater than zero. The coefficient
+    /// is `1 - 2^(-1 / half_life_samples)`, see [`Self::try_from_alpha`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `half_life_samples`: Half-life in samples (e.g., 100.0).
+    pub fn try_from_half_life_samples(
+        sample_rate_hz: T,
+        half_life_samples: T,
+    ) -> Result<Self, FilterError> {
+        let half_life_samples = check_time_constant(half_life_samples)?;
+        // 1 - 2^(-1/h), without cancellation for long half-lives
+        let alpha = -math::exp_m1(-LN_2 / half_life_samples);
+        Self::try_from_alpha(sample_rate_hz, T::from_f64(alpha))
+    }
+
+    /// Create a new lowpass filter that behaves like an exponential moving
+    /// average (EMA) with the period `N`, i.e., `alpha = 2 / (N + 1)`.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `period`: EMA period in samples (e.g., 20.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_from_ema_period`] for
+    /// the fallible variant.
+    #[must_use]
+    pub fn from_ema_period(sample_rate_hz: T, period: T) -> Self {
+        match Self::try_from_ema_period(sample_rate_hz, period) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new lowpass filter that behaves like an EMA with the period
+    /// `N` or return an error if the parameters are invalid.
+    ///
+    /// The period must be greater than one, so that `alpha` is smaller than
+    /// one, see [`Self::try_from_alpha`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `period`: EMA period in samples (e.g., 20.0).
+    pub fn try_from_ema_period(sample_rate_hz: T, period: T) -> Result<Self, FilterError> {
+        let alpha = 2.0 / (period.to_f64() + 1.0);
+        Self::try_from_alpha(sample_rate_hz, T::from_f64(alpha))
+    }
+}
+
+impl<T: Sample, R: RangePolicy<T>> LowpassFilter<T, R> {
+    /// Returns the coefficient `alpha` that belongs to the current
+    /// parameters.
+    ///
+    /// If coefficient smoothing is active, the filter might still be on its
+    /// way to this coefficient.
+    #[must_use]
+    // the current `alpha` is an implementation detail of the smoothing
+    #[allow(clippy::misnamed_getters)]
+    pub const fn alpha(&self) -> T {
+        self.alpha_target
+    }
+
+    /// Returns the time constant `τ` of the equivalent RC circuit in
+    /// seconds, i.e., `(1 - alpha) / (alpha * sample_rate)`.
+    #[must_use]
+    pub fn time_constant_s(&self) -> T {
+        (T::ONE - self.alpha_target) / (self.alpha_target * self.sample_rate_hz)
+    }
+}
+
+/// Checks a time constant, rise time, or half-life and returns it as `f64`.
+fn check_time_constant<T: Sample>(value: T) -> Result<f64, FilterError> {
+    let value = value.to_f64();
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(FilterError::InvalidTimeConstant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternative_constructors() {
+        let filter = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        let from_alpha = LowpassFilter::<f64>::from_alpha(44100.0, filter.alpha());
+        assert_eq!(from_alpha.alpha(), filter.alpha());
+        assert!((from_alpha.cutoff_frequency_hz() - 1000.0).abs() < 1e-9);
+
+        let from_time_constant = LowpassFilter::<f64>::from_time_constant(44100.0, 0.01);
+        assert!((from_time_constant.time_constant_s() - 0.01).abs() < 1e-12);
+        assert!((from_time_constant.cutoff_frequency_hz() - 1.0 / (2.0 * PI * 0.01)).abs() < 1e-9);
+
+        let from_ema_period = LowpassFilter::<f32>::from_ema_period(100.0, 9.0);
+        assert_eq!(from_ema_period.alpha(), 0.2);
+    }
+
+    #[test]
+    fn test_step_responses() {
+        // the distance halves every 10 samples
+        let mut filter = LowpassFilter::<f64>::from_half_life_samples(44100.0, 10.0);
+        let mut output = 0.0;
+        for _ in 0..10 {
+            output = filter.run(1.0);
+        }
+        assert!((output - 0.5).abs() < 1e-12, "{output}");
+
+        // 10% to 90% within 20 ms
+        let mut filter = LowpassFilter::<f64>::from_rise_time(48000.0, 0.02);
+        let outputs = (0..48000)
+            .map(|_| filter.run(1.0))
+            .collect::<std::vec::Vec<_>>();
+        let t10 = outputs.iter().position(|x| *x >= 0.1).unwrap();
+        let t90 = outputs.iter().position(|x| *x >= 0.9).unwrap();
+        let rise_time = (t90 - t10) as f64 / 48000.0;
+        assert!((rise_time - 0.02).abs() < 1e-4, "{rise_time}");
+    }
+
+    #[test]
+    fn test_alternative_constructors_validation() {
+        type F = LowpassFilter<f64>;
+        assert_eq!(
+            F::try_from_alpha(44100.0, 0.0).unwrap_err(),
+            FilterError::InvalidAlpha
+        );
+        assert_eq!(
+            F::try_from_alpha(44100.0, f64::NAN).unwrap_err(),
+            FilterError::InvalidAlpha
+        );
+        assert_eq!(F::try_from_alpha(44100.0, 0.9).unwrap().alpha(), 0.9);
+        assert_eq!(
+            F::try_from_alpha(0.0, 0.5).unwrap_err(),
+            FilterError::InvalidSampleRate
+        );
+        assert_eq!(
+            F::try_from_time_constant(44100.0, -1.0).unwrap_err(),
+            FilterError::InvalidTimeConstant
+        );
+        assert_eq!(
+            F::try_from_rise_time(44100.0, f64::INFINITY).unwrap_err(),
+            FilterError::InvalidTimeConstant
+        );
+        assert_eq!(
+            F::try_from_half_life_samples(44100.0, 0.0).unwrap_err(),
+            FilterError::InvalidTimeConstant
+        );
+        assert_eq!(
+            F::try_from_ema_period(44100.0, 1.0).unwrap_err(),
+            FilterError::InvalidAlpha
+        );
+        assert_eq!(F::try_from_ema_period(44100.0, 1.5).unwrap().alpha(), 0.8);
+        assert!(F::try_from_ema_period(44100.0, 1.7).is_ok());
+    }
+}
@@ -0,0 +1,169 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`FilterError`].
+
+use core::fmt::{Display, Formatter};
+
+/// Errors that can happen when a filter is created with invalid parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterError {
+    /// The sample rate is zero, negative, NaN, or infinite.
+    InvalidSampleRate,
+    /// The cutoff frequency is zero, negative, NaN, or infinite.
+    InvalidCutoffFrequency,
+    /// The cutoff frequency is above the Nyquist frequency, i.e., above half
+    /// of the sample rate.
+    NyquistViolation,
+    /// The lower cutoff frequency of a band is not smaller than the upper
+    /// cutoff frequency.
+    InvalidBand,
+    /// The quality factor (Q) is zero, negative, NaN, or infinite.
+    InvalidQualityFactor,
+    /// The order of the filter is not supported.
+    InvalidOrder,
+    /// The smoothing time is negative, NaN, or infinite.
+    InvalidSmoothingTime,
+    /// The filter coefficient can't be represented in the fixed-point format,
+    /// e.g., because it would round to zero.
+    UnrepresentableCoefficient,
+    /// The lower bound of a range is greater than the upper bound or one of
+    /// them is NaN.
+    InvalidRange,
+    /// The version of a serialized filter is not supported.
+    UnsupportedVersion,
+    /// The state of a serialized filter is inconsistent or invalid.
+    InvalidState,
+    /// A time constant, rise time, or half-life is zero, negative, NaN, or
+    /// infinite.
+    InvalidTimeConstant,
+    /// The filter coefficient (alpha) is not in the range `0.0..1.0` or NaN,
+    /// for example, because an EMA period is not greater than one.
+    InvalidAlpha,
+    /// The re-sync threshold of a timestamped filter is negative or NaN.
+    InvalidResyncThreshold,
+}
+
+impl FilterError {
+    /// Checks the parameters that all filters have in common.
+    ///
+    /// The values are checked as `f64`, so that `f32` and `f64` share the
+    /// same logic. Widening an `f32` is lossless.
+    pub(crate) fn check_frequencies(
+        sample_rate_hz: f64,
+        cutoff_frequency_hz: f64,
+    ) -> Result<(), Self> {
+        Self::check_sample_rate(sample_rate_hz)?;
+        Self::check_cutoff_frequency(cutoff_frequency_hz)?;
+        if cutoff_frequency_hz * 2.0 > sample_rate_hz {
+            Err(Self::NyquistViolation)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the cutoff frequency on its own, for a cutoff frequency that
+    /// is only the equivalent of a coefficient and may be above the Nyquist
+    /// frequency.
+    pub(crate) fn check_cutoff_frequency(cutoff_frequency_hz: f64) -> Result<(), Self> {
+        if !cutoff_frequency_hz.is_finite() || cutoff_frequency_hz <= 0.0 {
+            Err(Self::InvalidCutoffFrequency)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the sample rate on its own, for parameters that need it before
+    /// the cutoff frequency can be derived.
+    pub(crate) fn check_sample_rate(sample_rate_hz: f64) -> Result<(), Self> {
+        if !sample_rate_hz.is_finite() || sample_rate_hz <= 0.0 {
+            Err(Self::InvalidSampleRate)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for FilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidSampleRate => {
+                write!(
+                    f,
+                    "the sample rate must be a finite number greater than zero"
+                )
+            }
+            Self::InvalidCutoffFrequency => write!(
+                f,
+                "the cutoff frequency must be a finite number greater than zero"
+            ),
+            Self::NyquistViolation => write!(
+                f,
+                "the cutoff frequency must not be above the Nyquist frequency (half the sample rate)"
+            ),
+            Self::InvalidQualityFactor => write!(
+                f,
+                "the quality factor must be a finite number greater than zero"
+            ),
+            Self::InvalidOrder => write!(f, "the order of the filter is not supported"),
+            Self::InvalidSmoothingTime => write!(
+                f,
+                "the smoothing time must be a finite number that is not negative"
+            ),
+            Self::UnrepresentableCoefficient => write!(
+                f,
+                "the filter coefficient can't be represented in the fixed-point format"
+            ),
+            Self::InvalidRange => write!(
+                f,
+                "the lower bound of the range must not be greater than the upper bound"
+            ),
+            Self::UnsupportedVersion => {
+                write!(f, "the version of the serialized filter is not supported")
+            }
+            Self::InvalidState => write!(
+                f,
+                "the state of the serialized filter is inconsistent or invalid"
+            ),
+            Self::InvalidTimeConstant => write!(
+                f,
+                "the time constant must be a finite number greater than zero"
+            ),
+            Self::InvalidAlpha => write!(
+                f,
+                "the filter coefficient must be greater than zero and smaller than one"
+            ),
+            Self::InvalidBand => write!(
+                f,
+                "the lower cutoff frequency of the band must be smaller than the upper cutoff frequency"
+            ),
+            Self::InvalidResyncThreshold => write!(
+                f,
+                "the re-sync threshold must be a number that is not negative"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for FilterError {}
@@ -0,0 +1,221 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for zero-phase forward-backward filtering, such as
+//! [`lowpass_filtfilt`].
+
+use crate::{FilterError, LowpassFilter, Sample};
+
+/// Length of the padding at both edges in time constants of the filter.
+const PADDING_TIME_CONSTANTS: f64 = 10.0;
+
+/// One step of the lowpass recurrence without the range check and the
+/// clamping of [`LowpassFilter::run`], as the padding may exceed the range.
+#[inline]
+fn step<T: Sample>(state: T, alpha: T, input: T) -> T {
+    state + alpha * (input - state)
+}
+
+/// Applies a [`LowpassFilter`] forward and then backward to the data provided
+/// in the mutable buffer and changes the items in-place.
+///
+/// Unlike [`crate::lowpass_filter`], the result has no phase lag, which is
+/// useful for offline analysis of recordings. The magnitude response is
+/// squared, i.e., the attenuation in dB is doubled. Hence, the cutoff
+/// frequency is at -6 dB instead of -3 dB.
+///
+/// To avoid transients at the start and at the end, the signal is extended
+/// at both edges by an odd reflection and the filter state is initialized to
+/// the steady state of the first and last value, similar to
+/// `scipy.signal.filtfilt`. The padding is ten time constants long, but at
+/// most one sample shorter than the signal. No allocations are required.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+///
+/// # Arguments
+/// - `samples`: The buffer with the whole signal.
+/// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+/// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_lowpass_filtfilt`] for the
+/// fallible variant.
+pub fn lowpass_filtfilt<T: Sample>(samples: &mut [T], sample_rate_hz: T, cutoff_frequency_hz: T) {
+    if let Err(e) = try_lowpass_filtfilt(samples, sample_rate_hz, cutoff_frequency_hz) {
+        panic!("invalid lowpass filter parameters: {e}");
+    }
+}
+
+/// Fallible variant of [`lowpass_filtfilt`]. The buffer is not touched if
+/// the parameters are invalid.
+///
+/// # Arguments
+/// - `samples`: The buffer with the whole signal.
+/// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+/// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+pub fn try_lowpass_filtfilt<T: Sample>(
+    samples: &mut [T],
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+) -> Result<(), FilterError> {
+    let alpha = LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?.alpha;
+
+    // A single sample is its own steady state.
+    let len = samples.len();
+    if len < 2 {
+        return Ok(());
+    }
+
+    // The time constant of the filter is `1 / alpha` samples.
+    let padlen = ((PADDING_TIME_CONSTANTS / alpha.to_f64()) as usize).min(len - 1);
+    let two = T::ONE + T::ONE;
+    let decay = T::ONE - alpha;
+    let first = samples[0];
+    let last = samples[len - 1];
+
+    // The padding at the end only contributes to the initial state of the
+    // backward pass. This state is linear in the final state of the forward
+    // pass: `gain * state + offset`. Both are calculated here, as the
+    // odd reflection requires the original values, which are overwritten by
+    // the forward pass. This way, no buffer is needed for the padding.
+    let mut zero_state_response = T::ZERO;
+    let mut unit_state_response = T::ONE;
+    let mut weight = T::ONE;
+    let mut gain = T::ZERO;
+    let mut offset = T::ZERO;
+    for i in 0..padlen {
+        let padding = two * last - samples[len - 2 - i];
+        zero_state_response = step(zero_state_response, alpha, padding);
+        unit_state_response = unit_state_response * decay;
+        offset = offset + alpha * weight * zero_state_response;
+        gain = gain + alpha * weight * unit_state_response;
+        weight = weight * decay;
+    }
+    // the backward pass starts in the steady state of the last output
+    offset = offset + weight * zero_state_response;
+    gain = gain + weight * unit_state_response;
+
+    // Forward pass: starts in the steady state of the first padding value.
+    // The outputs of the padding at the start are not needed.
+    let mut state = two * first - samples[padlen];
+    for i in (1..=padlen).rev() {
+        state = step(state, alpha, two * first - samples[i]);
+    }
+    for sample in samples.iter_mut() {
+        state = step(state, alpha, *sample);
+        *sample = state;
+    }
+
+    // Backward pass
+    let mut state = gain * state + offset;
+    for sample in samples.iter_mut().rev() {
+        state = step(state, alpha, *sample);
+        // very small deviations caused by floating point operations
+        // are tolerable; just truncate the value
+        *sample = state.clamp(-T::ONE, T::ONE);
+    }
+
+    Ok(())
+}
+
+/// Same as [`lowpass_filtfilt`] but restricted to `f64`.
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_lowpass_filtfilt_f64`] for the
+/// fallible variant.
+#[inline]
+pub fn lowpass_filtfilt_f64(samples: &mut [f64], sample_rate_hz: f64, cutoff_frequency_hz: f64) {
+    lowpass_filtfilt(samples, sample_rate_hz, cutoff_frequency_hz);
+}
+
+/// Same as [`try_lowpass_filtfilt`] but restricted to `f64`.
+#[inline]
+pub fn try_lowpass_filtfilt_f64(
+    samples: &mut [f64],
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) -> Result<(), FilterError> {
+    try_lowpass_filtfilt(samples, sample_rate_hz, cutoff_frequency_hz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+    use std::vec::Vec;
+
+    /// Returns the maximum absolute difference of two signals.
+    fn max_diff(a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_filtfilt_has_no_phase_lag() {
+        let samples = sine_wave_samples(20.0, 44100.0);
+
+        let mut causal = samples.clone();
+        crate::lowpass_filter_f64(&mut causal, 44100.0, 200.0);
+        let mut zero_phase = samples.clone();
+        lowpass_filtfilt_f64(&mut zero_phase, 44100.0, 200.0);
+
+        // the magnitude is barely changed far below the cutoff frequency,
+        // so only a phase lag would cause a deviation
+        assert!(max_diff(&causal, &samples) > 0.05);
+        assert!(max_diff(&zero_phase, &samples) < 0.02);
+    }
+
+    #[test]
+    fn test_filtfilt_has_no_transients() {
+        let mut constant = [0.5_f32; 100];
+        lowpass_filtfilt(&mut constant, 44100.0, 120.0);
+        assert!(constant.iter().all(|x| (x - 0.5).abs() < 1e-6));
+
+        // a ramp is continued by the odd reflection
+        let ramp = (0..1000).map(|i| f64::from(i) / 1000.0).collect::<Vec<_>>();
+        let mut filtered = ramp.clone();
+        lowpass_filtfilt_f64(&mut filtered, 44100.0, 500.0);
+        assert!(max_diff(&ramp, &filtered) < 1e-3);
+    }
+
+    #[test]
+    fn test_filtfilt_short_buffers_and_validation() {
+        for len in 0..10 {
+            let mut samples = [0.25; 10];
+            lowpass_filtfilt_f64(&mut samples[..len], 44100.0, 120.0);
+        }
+        let mut single = [0.25];
+        lowpass_filtfilt_f64(&mut single, 44100.0, 120.0);
+        assert_eq!(single, [0.25]);
+
+        let mut samples = [0.25; 4];
+        assert_eq!(
+            try_lowpass_filtfilt_f64(&mut samples, 44100.0, 30000.0),
+            Err(FilterError::NyquistViolation)
+        );
+        assert_eq!(samples, [0.25; 4]);
+    }
+}
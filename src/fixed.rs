@@ -0,0 +1,317 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the fixed-point lowpass filters [`LowpassFilterQ15`] and
+//! [`LowpassFilterQ31`].
+//!
+//! They are meant for microcontrollers without an FPU, where `f32` and `f64`
+//! are emulated in software and hence very slow. Only the construction of a
+//! filter uses floating-point math.
+
+use crate::{Filter, FilterError};
+use core::f64::consts::PI;
+
+/// How the filter coefficient is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coefficient<W> {
+    /// Multiplication with `alpha` in Q format.
+    Multiply(W),
+    /// Arithmetic right shift by `k`, i.e., `alpha = 2^-k`.
+    Shift(u32),
+}
+
+macro_rules! impl_fixed_point_lowpass_filter {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        sample: $sample:ty,
+        acc: $acc:ty,
+        wide: $wide:ty,
+        coefficient_bits: $coefficient_bits:expr,
+        extra_bits: $extra_bits:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            coefficient: Coefficient<$wide>,
+            /// The previous output, shifted left by `EXTRA_BITS`.
+            acc: $acc,
+        }
+
+        impl $name {
+            /// Number of fractional bits of the coefficient.
+            const COEFFICIENT_BITS: u32 = $coefficient_bits;
+            /// Number of additional fractional bits of the internal state.
+            const EXTRA_BITS: u32 = $extra_bits;
+
+            /// Create a new fixed-point lowpass filter. The coefficient is
+            /// derived from the same RC model as in
+            /// [`crate::LowpassFilter::new`] and rounded to the nearest Q
+            /// format value.
+            ///
+            /// # Arguments
+            /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+            /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+            ///
+            /// # Panics
+            /// If the parameters are invalid. See [`Self::try_new`] for the
+            /// fallible variant.
+            #[must_use]
+            pub fn new(sample_rate_hz: f64, cutoff_frequency_hz: f64) -> Self {
+                match Self::try_new(sample_rate_hz, cutoff_frequency_hz) {
+                    Ok(filter) => filter,
+                    Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+                }
+            }
+
+            /// Create a new fixed-point lowpass filter or return an error if
+            /// the parameters are invalid.
+            ///
+            /// The parameters are validated the same way as in
+            /// [`crate::LowpassFilter::try_new`]. Additionally, the cutoff
+            /// frequency must be high enough so that the coefficient doesn't
+            /// round to zero.
+            ///
+            /// # Arguments
+            /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+            /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+            pub fn try_new(
+                sample_rate_hz: f64,
+                cutoff_frequency_hz: f64,
+            ) -> Result<Self, FilterError> {
+                FilterError::check_frequencies(sample_rate_hz, cutoff_frequency_hz)?;
+
+                let rc = 1.0 / (cutoff_frequency_hz * 2.0 * PI);
+                let dt = 1.0 / sample_rate_hz;
+                let alpha = dt / (rc + dt);
+
+                let scale = (1_u64 << Self::COEFFICIENT_BITS) as f64;
+                // alpha is positive, so this rounds to the nearest value
+                let alpha = (alpha * scale + 0.5) as $wide;
+                if alpha == 0 {
+                    return Err(FilterError::UnrepresentableCoefficient);
+                }
+
+                Ok(Self {
+                    coefficient: Coefficient::Multiply(alpha),
+                    acc: 0,
+                })
+            }
+
+            /// Create a new fixed-point lowpass filter with the coefficient
+            /// `alpha = 2^-k`.
+            ///
+            /// This replaces the multiplication with a cheap arithmetic
+            /// shift. The cutoff frequency is approximately
+            /// `sample_rate_hz / (2π * (2^k - 1))`.
+            ///
+            /// # Panics
+            /// If `k` is invalid. See [`Self::try_from_shift`] for the
+            /// fallible variant.
+            #[must_use]
+            pub fn from_shift(k: u32) -> Self {
+                match Self::try_from_shift(k) {
+                    Ok(filter) => filter,
+                    Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+                }
+            }
+
+            /// Create a new fixed-point lowpass filter with the coefficient
+            /// `alpha = 2^-k` or return an error if `k` is not in the range
+            /// from 1 up to and including the number of fractional bits of
+            /// the Q format.
+            pub fn try_from_shift(k: u32) -> Result<Self, FilterError> {
+                if (1..=Self::COEFFICIENT_BITS).contains(&k) {
+                    Ok(Self {
+                        coefficient: Coefficient::Shift(k),
+                        acc: 0,
+                    })
+                } else {
+                    Err(FilterError::UnrepresentableCoefficient)
+                }
+            }
+
+            /// Filter a single sample and return the filtered result.
+            #[inline]
+            pub fn run(&mut self, input: $sample) -> $sample {
+                let diff = (<$acc>::from(input) << Self::EXTRA_BITS) - self.acc;
+                let step = match self.coefficient {
+                    Coefficient::Multiply(alpha) => {
+                        let product = alpha * <$wide>::from(diff);
+                        let half = 1 << (Self::COEFFICIENT_BITS - 1);
+                        ((product + half) >> Self::COEFFICIENT_BITS) as $acc
+                    }
+                    Coefficient::Shift(k) => {
+                        let half = 1 << (k - 1);
+                        (diff + half) >> k
+                    }
+                };
+                self.acc += step;
+
+                let output = if Self::EXTRA_BITS == 0 {
+                    self.acc
+                } else {
+                    (self.acc + (1 << Self::EXTRA_BITS.saturating_sub(1))) >> Self::EXTRA_BITS
+                };
+                output.clamp(<$acc>::from(<$sample>::MIN), <$acc>::from(<$sample>::MAX)) as $sample
+            }
+
+            /// Reset the internal filter state.
+            pub const fn reset(&mut self) {
+                self.acc = 0;
+            }
+        }
+
+        impl Filter<$sample> for $name {
+            #[inline]
+            fn run(&mut self, input: $sample) -> $sample {
+                Self::run(self, input)
+            }
+
+            fn reset(&mut self) {
+                Self::reset(self);
+            }
+        }
+    };
+}
+
+impl_fixed_point_lowpass_filter!(
+    /// A fixed-point lowpass filter for `i16` samples with a Q15 coefficient.
+    ///
+    /// The filter starts with a state of zero.
+    ///
+    /// # Rounding and Overflow
+    /// - The state keeps 15 additional fractional bits in an `i32`, so small
+    ///   differences between input and output are not lost. The output
+    ///   settles exactly on a constant input.
+    /// - The product of the coefficient and the difference is calculated
+    ///   with an `i64`. The result and the output are rounded to the nearest
+    ///   value; ties round towards positive infinity.
+    /// - In shift mode (`alpha = 2^-k`), the same rounding applies and no
+    ///   multiplication is needed at all.
+    /// - Overflows are impossible: the state is always a weighted average of
+    ///   past inputs, and the output saturates at the bounds of `i16`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lowpass_filter::LowpassFilterQ15;
+    ///
+    /// let mut filter = LowpassFilterQ15::new(44100.0, 120.0);
+    /// let _filtered = filter.run(i16::MAX);
+    /// ```
+    LowpassFilterQ15,
+    sample: i16,
+    acc: i32,
+    wide: i64,
+    coefficient_bits: 15,
+    extra_bits: 15
+);
+
+impl_fixed_point_lowpass_filter!(
+    /// A fixed-point lowpass filter for `i32` samples with a Q31 coefficient.
+    ///
+    /// The filter starts with a state of zero.
+    ///
+    /// # Rounding and Overflow
+    /// - The state has no additional fractional bits, as the resolution of
+    ///   `i32` is already high. Hence, the output might settle up to
+    ///   `0.5 / alpha` LSBs away from a constant input.
+    /// - The product of the coefficient and the difference is calculated
+    ///   with an `i64`. The result is rounded to the nearest value; ties
+    ///   round towards positive infinity.
+    /// - In shift mode (`alpha = 2^-k`), the same rounding applies and no
+    ///   multiplication is needed at all.
+    /// - Overflows are impossible: the state is always a weighted average of
+    ///   past inputs, and the output saturates at the bounds of `i32`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lowpass_filter::LowpassFilterQ31;
+    ///
+    /// let mut filter = LowpassFilterQ31::from_shift(6);
+    /// let _filtered = filter.run(i32::MAX);
+    /// ```
+    LowpassFilterQ31,
+    sample: i32,
+    acc: i64,
+    wide: i64,
+    coefficient_bits: 31,
+    extra_bits: 0
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LowpassFilter;
+    use crate::test_util::sine_wave_samples;
+
+    #[test]
+    fn test_q15_matches_float_filter() {
+        let mut float = LowpassFilter::<f64>::new(44100.0, 120.0);
+        let mut fixed = LowpassFilterQ15::new(44100.0, 120.0);
+
+        for sample in sine_wave_samples(90.0, 44100.0) {
+            let expected = float.run(sample * 0.9) * f64::from(i16::MAX);
+            let actual = fixed.run((sample * 0.9 * f64::from(i16::MAX)) as i16);
+            // a few LSBs of deviation come from rounding alpha to Q15
+            assert!((f64::from(actual) - expected).abs() <= 8.0);
+        }
+    }
+
+    #[test]
+    fn test_q31_matches_float_filter() {
+        let mut float = LowpassFilter::<f64>::new(44100.0, 500.0);
+        let mut fixed = LowpassFilterQ31::new(44100.0, 500.0);
+
+        let scale = f64::from(i32::MAX);
+        for sample in sine_wave_samples(300.0, 44100.0) {
+            let expected = float.run(sample * 0.9) * scale;
+            let actual = fixed.run((sample * 0.9 * scale) as i32);
+            assert!((f64::from(actual) - expected).abs() / scale < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_settles_and_saturates() {
+        let mut q15 = LowpassFilterQ15::new(44100.0, 50.0);
+        let mut q15_shift = LowpassFilterQ15::from_shift(8);
+        let mut q31_shift = LowpassFilterQ31::from_shift(31);
+        for _ in 0..500_000 {
+            q15.run(12345);
+            q15_shift.run(i16::MIN);
+            q31_shift.run(i32::MAX);
+        }
+        assert_eq!(q15.run(12345), 12345);
+        assert_eq!(q15_shift.run(i16::MIN), i16::MIN);
+        assert!(q31_shift.run(i32::MAX) > 0);
+
+        assert_eq!(
+            LowpassFilterQ15::try_from_shift(16).unwrap_err(),
+            FilterError::UnrepresentableCoefficient
+        );
+        assert_eq!(
+            LowpassFilterQ15::try_new(44100.0, 0.01).unwrap_err(),
+            FilterError::UnrepresentableCoefficient
+        );
+    }
+}
@@ -0,0 +1,233 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`HighpassFilter`] and the corresponding convenience functions.
+
+use crate::{Filter, FilterError, Sample, apply_filter};
+use core::f64::consts::PI;
+
+/// A single-order highpass filter that consumes and emits items one by one.
+///
+/// This is the complement of [`crate::LowpassFilter`]. It removes the
+/// frequencies below the cutoff frequency, for example, to strip DC offsets
+/// and rumble from a signal.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP.
+///
+/// # More Info
+/// - <https://en.wikipedia.org/wiki/High-pass_filter#Algorithmic_implementation>
+#[derive(Debug, Clone)]
+pub struct HighpassFilter<T> {
+    alpha: T,
+    prev_input: T,
+    prev_output: T,
+    next_is_first: bool,
+}
+
+impl<T: Sample> HighpassFilter<T> {
+    /// Create a new highpass filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, cutoff_frequency_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid highpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new highpass filter or return an error if the
+    /// parameters are invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`crate::LowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        FilterError::check_frequencies(sample_rate_hz.to_f64(), cutoff_frequency_hz.to_f64())?;
+
+        let rc = T::ONE / (cutoff_frequency_hz * T::from_f64(2.0) * T::from_f64(PI));
+        let dt = T::ONE / sample_rate_hz;
+        let alpha = rc / (rc + dt);
+
+        Ok(Self {
+            alpha,
+            prev_input: T::ZERO,
+            prev_output: T::ZERO,
+            next_is_first: true,
+        })
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// It is mandatory to operate on values in range `-1.0..=1.0`,
+    /// which is also the default in DSP. The returned value is
+    /// clamped to that range.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        debug_assert!(
+            (-T::ONE..=T::ONE).contains(&input),
+            "samples must be in range -1.0..=1.0: {input:?}"
+        );
+
+        let value = if self.next_is_first {
+            self.next_is_first = false;
+            input
+        } else {
+            self.alpha * (self.prev_output + input - self.prev_input)
+        };
+        self.prev_input = input;
+        self.prev_output = value;
+
+        // Steep edges in the input can overshoot the range; just
+        // truncate the value.
+        value.clamp(-T::ONE, T::ONE)
+    }
+
+    /// Reset the internal filter state.
+    pub const fn reset(&mut self) {
+        self.prev_input = T::ZERO;
+        self.prev_output = T::ZERO;
+        self.next_is_first = true;
+    }
+}
+
+impl<T: Sample> Filter<T> for HighpassFilter<T> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+/// Applies a [`HighpassFilter`] to the data provided in the mutable buffer and
+/// changes the items in-place.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP. The function works with all [`Sample`] types,
+/// such as `f32` and `f64`.
+///
+/// # Arguments
+/// - `sample_iter`: Iterator over the samples. This can also be a
+///   `[1.0, ...]`-style slice
+/// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+/// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_highpass_filter`] for the
+/// fallible variant.
+#[inline]
+pub fn highpass_filter<'a, T: Sample + 'a, I: IntoIterator<Item = &'a mut T>>(
+    sample_iter: I,
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+) {
+    let mut filter = HighpassFilter::new(sample_rate_hz, cutoff_frequency_hz);
+    apply_filter(&mut filter, sample_iter);
+}
+
+/// Fallible variant of [`highpass_filter`]. The buffer is not touched if the
+/// parameters are invalid.
+///
+/// # Arguments
+/// - `sample_iter`: Iterator over the samples. This can also be a
+///   `[1.0, ...]`-style slice
+/// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+/// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+#[inline]
+pub fn try_highpass_filter<'a, T: Sample + 'a, I: IntoIterator<Item = &'a mut T>>(
+    sample_iter: I,
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+) -> Result<(), FilterError> {
+    let mut filter = HighpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?;
+    apply_filter(&mut filter, sample_iter);
+    Ok(())
+}
+
+/// Same as [`highpass_filter`] but restricted to `f64`.
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_highpass_filter_f64`] for the
+/// fallible variant.
+#[inline]
+pub fn highpass_filter_f64<'a, I: IntoIterator<Item = &'a mut f64>>(
+    sample_iter: I,
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) {
+    highpass_filter(sample_iter, sample_rate_hz, cutoff_frequency_hz);
+}
+
+/// Same as [`try_highpass_filter`] but restricted to `f64`.
+#[inline]
+pub fn try_highpass_filter_f64<'a, I: IntoIterator<Item = &'a mut f64>>(
+    sample_iter: I,
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) -> Result<(), FilterError> {
+    try_highpass_filter(sample_iter, sample_rate_hz, cutoff_frequency_hz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::energy;
+    use crate::test_util::sine_wave_samples;
+
+    #[test]
+    fn test_hpf_removes_low_frequencies() {
+        let mut samples_l = sine_wave_samples(50.0, 44100.0);
+        let mut samples_h = sine_wave_samples(5000.0, 44100.0);
+        let power_l_orig = energy(&samples_l);
+        let power_h_orig = energy(&samples_h);
+
+        highpass_filter_f64(samples_l.as_mut_slice(), 44100.0, 1000.0);
+        highpass_filter_f64(samples_h.as_mut_slice(), 44100.0, 1000.0);
+
+        let power_l = energy(&samples_l);
+        let power_h = energy(&samples_h);
+
+        assert!(power_l * 100.0 < power_l_orig);
+        assert!(power_h * 1.25 > power_h_orig);
+    }
+
+    #[test]
+    fn test_hpf_removes_dc() {
+        let mut filter = HighpassFilter::<f32>::new(44100.0, 20.0);
+        let last = (0..44100).map(|_| filter.run(0.5)).last().unwrap();
+        assert!(last.abs() < 0.001, "{last}");
+    }
+}
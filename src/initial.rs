@@ -0,0 +1,50 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`InitialState`].
+
+/// Defines the state of a filter before the first sample and after a reset.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::{InitialState, LowpassFilter};
+///
+/// // no transient at the start of a file
+/// let mut filter =
+///     LowpassFilter::<f32>::new(44100.0, 120.0).with_initial_state(InitialState::FirstSample);
+/// assert_eq!(filter.run(0.5), 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InitialState<T> {
+    /// The filter starts from zero, as if it had only seen silence. The
+    /// output fades in. This is the default.
+    Zero,
+    /// The filter is primed to the first sample, as if it had seen this
+    /// value forever. The first output equals the first input, so there is
+    /// no transient.
+    FirstSample,
+    /// The filter is primed to the given value, as if it had seen this
+    /// value forever.
+    Value(T),
+}
@@ -0,0 +1,363 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for filtering samples with irregular timestamps, e.g., telemetry
+//! with jitter, with [`LowpassFilter::run_with_dt`] and
+//! [`TimestampedLowpassFilter`].
+//!
+//! The coefficient is derived for each sample from the same RC model as in
+//! [`LowpassFilter::new`], i.e., `alpha = dt / (τ + dt)` with the time
+//! constant `τ = 1 / (2π * cutoff)`. For `dt = 1 / sample_rate`, this is the
+//! regular coefficient.
+
+use crate::{FilterError, LowpassFilter, Normalized, RangePolicy, Sample};
+use core::cmp::Ordering;
+
+impl<T: Sample, R: RangePolicy<T>> LowpassFilter<T, R> {
+    /// Filters a single sample that was taken `dt_s` seconds after the
+    /// previous one, instead of the fixed `1 / sample_rate`.
+    ///
+    /// The gap is handled as follows:
+    /// - `dt_s` is zero, negative, or NaN: the output is held, i.e., the
+    ///   sample has no effect.
+    /// - `dt_s` is large compared to the time constant: the output follows
+    ///   the input, as `alpha` approaches one. This includes infinity.
+    ///
+    /// The time constant of the current parameters is used, see
+    /// [`Self::time_constant_s`]. Coefficient smoothing is not applied, but
+    /// [`InitialState::FirstSample`](crate::InitialState::FirstSample) and
+    /// the [`RangePolicy`] are respected as in [`Self::run`].
+    ///
+    /// # Arguments
+    /// - `input`: The sample.
+    /// - `dt_s`: The time since the previous sample in seconds.
+    pub fn run_with_dt(&mut self, input: T, dt_s: T) -> T {
+        self.range.check_input(input);
+
+        if self.next_is_first {
+            self.next_is_first = false;
+            self.prev = input;
+        } else if dt_s > T::ZERO {
+            // Same as `dt / (τ + dt)`, but the sum can't overflow, so that
+            // huge gaps (and infinity) result in `alpha = 1`.
+            let alpha = T::ONE / (T::ONE + self.time_constant_s() / dt_s);
+            self.prev = self.prev + alpha * (input - self.prev);
+        }
+
+        self.range.limit(self.prev)
+    }
+}
+
+/// A lowpass filter for samples with absolute timestamps, e.g., telemetry
+/// that arrives with jitter.
+///
+/// The time between two samples is derived from their timestamps, see
+/// [`Self::run_at`] and [`LowpassFilter::run_with_dt`]. Timestamps are in
+/// seconds and can start at any value. For long recordings, use `f64`, as
+/// the precision of large `f32` timestamps is poor.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::TimestampedLowpassFilter;
+///
+/// // nominally 100 Hz, but the timestamps jitter
+/// let mut filter = TimestampedLowpassFilter::<f64>::new(100.0, 5.0);
+/// let a = filter.run_at(1.0, 12.000);
+/// let b = filter.run_at(1.0, 12.013);
+/// let c = filter.run_at(1.0, 12.018);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimestampedLowpassFilter<T, R = Normalized> {
+    filter: LowpassFilter<T, R>,
+    last_timestamp_s: Option<T>,
+    resync_threshold_s: T,
+}
+
+impl<T: Sample, R> TimestampedLowpassFilter<T, R> {
+    /// Creates a timestamped filter from an existing filter and its state,
+    /// for example one with a different [`RangePolicy`].
+    ///
+    /// The sample rate of the filter is the nominal sample rate, which is
+    /// only used before the first timestamp is known and after a re-sync.
+    /// The re-sync threshold is one second, see
+    /// [`Self::set_resync_threshold`].
+    #[must_use]
+    pub const fn from_filter(filter: LowpassFilter<T, R>) -> Self {
+        Self {
+            filter,
+            last_timestamp_s: None,
+            resync_threshold_s: T::ONE,
+        }
+    }
+
+    /// Sets how far, in seconds, a timestamp may go back before it is
+    /// treated as a reset of the clock, e.g., after a device restarted.
+    ///
+    /// Smaller steps back are out-of-order samples, which are ignored.
+    /// After a larger step back, the filter re-syncs to the new timestamp:
+    /// it keeps its output and continues as if the sample were the first
+    /// one, i.e., with the nominal `dt = 1 / sample_rate`. Use infinity to
+    /// never re-sync.
+    ///
+    /// # Panics
+    /// If the threshold is negative or NaN. See
+    /// [`Self::try_set_resync_threshold`] for the fallible variant.
+    pub fn set_resync_threshold(&mut self, threshold_s: T) {
+        if let Err(e) = self.try_set_resync_threshold(threshold_s) {
+            panic!("invalid re-sync threshold: {e}");
+        }
+    }
+
+    /// Fallible variant of [`Self::set_resync_threshold`].
+    pub fn try_set_resync_threshold(&mut self, threshold_s: T) -> Result<(), FilterError> {
+        // also rejects NaN
+        if threshold_s
+            .partial_cmp(&T::ZERO)
+            .is_none_or(Ordering::is_lt)
+        {
+            return Err(FilterError::InvalidResyncThreshold);
+        }
+
+        self.resync_threshold_s = threshold_s;
+        Ok(())
+    }
+}
+
+impl<T, R> TimestampedLowpassFilter<T, R> {
+    /// Returns the underlying filter.
+    #[must_use]
+    pub const fn filter(&self) -> &LowpassFilter<T, R> {
+        &self.filter
+    }
+
+    /// Returns the underlying filter, for example to change the cutoff
+    /// frequency with [`LowpassFilter::set_cutoff`].
+    #[must_use]
+    pub const fn filter_mut(&mut self) -> &mut LowpassFilter<T, R> {
+        &mut self.filter
+    }
+
+    /// Returns the underlying filter and its state.
+    #[must_use]
+    pub fn into_inner(self) -> LowpassFilter<T, R> {
+        self.filter
+    }
+}
+
+impl<T: Copy, R> TimestampedLowpassFilter<T, R> {
+    /// Returns the timestamp of the latest sample, if any.
+    #[must_use]
+    pub const fn last_timestamp_s(&self) -> Option<T> {
+        self.last_timestamp_s
+    }
+
+    /// Returns the re-sync threshold in seconds, see
+    /// [`TimestampedLowpassFilter::set_resync_threshold`].
+    #[must_use]
+    pub const fn resync_threshold_s(&self) -> T {
+        self.resync_threshold_s
+    }
+}
+
+impl<T: Sample> TimestampedLowpassFilter<T> {
+    /// Create a new timestamped lowpass filter.
+    ///
+    /// # Arguments
+    /// - `nominal_sample_rate_hz`: The expected sample rate in Hz (e.g.,
+    ///   100.0). It is only used for the first sample.
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 5.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(nominal_sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        Self::from_filter(LowpassFilter::new(
+            nominal_sample_rate_hz,
+            cutoff_frequency_hz,
+        ))
+    }
+
+    /// Create a new timestamped lowpass filter or return an error if the
+    /// parameters are invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`LowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `nominal_sample_rate_hz`: The expected sample rate in Hz (e.g.,
+    ///   100.0). It is only used for the first sample.
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 5.0).
+    pub fn try_new(nominal_sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        LowpassFilter::try_new(nominal_sample_rate_hz, cutoff_frequency_hz).map(Self::from_filter)
+    }
+}
+
+impl<T: Sample, R: RangePolicy<T>> TimestampedLowpassFilter<T, R> {
+    /// Filters a single sample that was taken at the given timestamp in
+    /// seconds.
+    ///
+    /// The first sample is filtered with the nominal `dt = 1 / sample_rate`.
+    /// A sample whose timestamp is not after the latest one (duplicates or
+    /// out-of-order samples) or is NaN is ignored and the output is held.
+    /// If the timestamp goes back by more than the re-sync threshold, the
+    /// clock is assumed to be reset and the sample is filtered like the
+    /// first one, see [`Self::set_resync_threshold`]. See
+    /// [`LowpassFilter::run_with_dt`] for details.
+    pub fn run_at(&mut self, input: T, timestamp_s: T) -> T {
+        let dt_s = match self.last_timestamp_s {
+            Some(last_timestamp_s)
+                if last_timestamp_s - timestamp_s > self.resync_threshold_s
+                    && timestamp_s.is_finite() =>
+            {
+                T::ONE / self.filter.sample_rate_hz
+            }
+            Some(last_timestamp_s) => timestamp_s - last_timestamp_s,
+            None if timestamp_s.is_finite() => T::ONE / self.filter.sample_rate_hz,
+            None => T::ZERO,
+        };
+        if dt_s > T::ZERO {
+            self.last_timestamp_s = Some(timestamp_s);
+        }
+        self.filter.run_with_dt(input, dt_s)
+    }
+
+    /// Resets the filter to its initial state and forgets the latest
+    /// timestamp.
+    pub const fn reset(&mut self) {
+        self.filter.reset();
+        self.last_timestamp_s = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InitialState, Unbounded};
+
+    #[test]
+    fn test_run_with_dt_matches_run() {
+        let mut filter = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        let mut reference = filter.clone();
+        for i in 0..1000 {
+            let input = (f64::from(i) * 0.01).sin() * 0.9;
+            let expected = reference.run(input);
+            let actual = filter.run_with_dt(input, 1.0 / 44100.0);
+            assert!((expected - actual).abs() < 1e-12, "{expected} != {actual}");
+        }
+    }
+
+    #[test]
+    fn test_run_with_dt_gaps() {
+        let mut filter = LowpassFilter::<f32>::new(100.0, 5.0);
+        let held = filter.run_with_dt(0.5, 0.01);
+
+        assert_eq!(filter.run_with_dt(1.0, 0.0), held);
+        assert_eq!(filter.run_with_dt(1.0, -1.0), held);
+        assert_eq!(filter.run_with_dt(1.0, f32::NAN), held);
+
+        assert!((filter.run_with_dt(0.8, 1e30) - 0.8).abs() < 1e-6);
+        assert_eq!(filter.run_with_dt(0.3, f32::MAX), 0.3);
+        assert_eq!(filter.run_with_dt(0.2, f32::INFINITY), 0.2);
+
+        let mut filter =
+            LowpassFilter::<f32>::new(100.0, 5.0).with_initial_state(InitialState::FirstSample);
+        assert_eq!(filter.run_with_dt(0.7, 0.0), 0.7);
+    }
+
+    #[test]
+    fn test_run_at_with_jitter() {
+        // step response with jittery timestamps, compared to the analog RC
+        // circuit
+        let mut filter = TimestampedLowpassFilter::<f64, Unbounded>::from_filter(
+            LowpassFilter::new(1000.0, 1.0).with_range_policy(Unbounded),
+        );
+        let tau = filter.filter().time_constant_s();
+        let mut timestamp_s = 1000.0;
+        filter.run_at(0.0, timestamp_s);
+        let start_s = timestamp_s;
+        let mut output = 0.0;
+        for i in 0..5000 {
+            timestamp_s += [0.0005, 0.0015, 0.001, 0.0002][i % 4];
+            output = filter.run_at(1.0, timestamp_s);
+        }
+        let expected = 1.0 - (-(timestamp_s - start_s) / tau).exp();
+        assert!((output - expected).abs() < 1e-3, "{output} != {expected}");
+    }
+
+    #[test]
+    fn test_run_at_out_of_order() {
+        let mut filter = TimestampedLowpassFilter::<f64>::new(100.0, 5.0);
+        let first = filter.run_at(0.5, 3.0);
+        assert_eq!(filter.last_timestamp_s(), Some(3.0));
+
+        assert_eq!(filter.run_at(1.0, 3.0), first);
+        assert_eq!(filter.run_at(1.0, 2.0), first);
+        assert_eq!(filter.run_at(1.0, f64::NAN), first);
+        assert_eq!(filter.last_timestamp_s(), Some(3.0));
+
+        assert!(filter.run_at(1.0, 3.01) > first);
+        assert_eq!(filter.last_timestamp_s(), Some(3.01));
+
+        filter.reset();
+        assert_eq!(filter.last_timestamp_s(), None);
+        assert_eq!(filter.run_at(0.5, 0.0), first);
+    }
+
+    #[test]
+    fn test_run_at_resyncs_after_clock_reset() {
+        let mut filter = TimestampedLowpassFilter::<f64>::new(100.0, 5.0);
+        assert_eq!(filter.resync_threshold_s(), 1.0);
+        filter.run_at(0.0, 500.0);
+        let before = filter.run_at(1.0, 500.01);
+
+        // out of order, but within the threshold
+        assert_eq!(filter.run_at(1.0, 499.5), before);
+        assert_eq!(filter.last_timestamp_s(), Some(500.01));
+
+        // the clock was reset: the state is kept and filtering continues
+        // with the nominal sample rate
+        let mut reference = filter.filter().clone();
+        let after = filter.run_at(1.0, 0.0);
+        assert_eq!(after, reference.run_with_dt(1.0, 0.01));
+        assert!(after > before);
+        assert_eq!(filter.last_timestamp_s(), Some(0.0));
+        assert!(filter.run_at(1.0, 0.01) > after);
+        assert_eq!(filter.last_timestamp_s(), Some(0.01));
+
+        filter.set_resync_threshold(f64::INFINITY);
+        let held = filter.run_at(1.0, -1000.0);
+        assert_eq!(filter.run_at(1.0, -1000.0), held);
+        assert_eq!(filter.last_timestamp_s(), Some(0.01));
+
+        assert_eq!(
+            filter.try_set_resync_threshold(-1.0),
+            Err(FilterError::InvalidResyncThreshold)
+        );
+        assert_eq!(
+            filter.try_set_resync_threshold(f64::NAN),
+            Err(FilterError::InvalidResyncThreshold)
+        );
+        assert_eq!(filter.try_set_resync_threshold(0.0), Ok(()));
+    }
+}
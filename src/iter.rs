@@ -0,0 +1,223 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`FilterIteratorExt`] and the iterator adapters it returns.
+
+use crate::{Filter, FilterError, LowpassFilter, Sample};
+use core::iter::FusedIterator;
+
+/// Extension trait that integrates the filters of this crate into iterator
+/// chains.
+///
+/// It is implemented for all iterators over [`Sample`] types. These are
+/// `f32` and `f64` or, with the `num-traits` feature, every type that
+/// implements `num_traits::float::FloatCore`.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::{FilterIteratorExt, LowpassFilter};
+///
+/// let samples = [0.0_f32, 0.5, 1.0, -0.25];
+///
+/// // The adapter owns the filter.
+/// let filtered = samples
+///     .iter()
+///     .copied()
+///     .lowpass(44100.0, 120.0)
+///     .collect::<Vec<_>>();
+///
+/// // The adapter borrows the filter, which keeps its state for the next
+/// // buffer.
+/// let mut filter = LowpassFilter::<f32>::new(44100.0, 120.0);
+/// let filtered = samples
+///     .iter()
+///     .copied()
+///     .filtered_by(&mut filter)
+///     .collect::<Vec<_>>();
+/// ```
+pub trait FilterIteratorExt<T>: Iterator<Item = T> + Sized {
+    /// Filters all items with a new [`LowpassFilter`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_lowpass`] for the
+    /// fallible variant.
+    fn lowpass(self, sample_rate_hz: T, cutoff_frequency_hz: T)
+    -> Filtered<Self, LowpassFilter<T>>;
+
+    /// Fallible variant of [`Self::lowpass`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    fn try_lowpass(
+        self,
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+    ) -> Result<Filtered<Self, LowpassFilter<T>>, FilterError>;
+
+    /// Filters all items with the given filter, which is owned by the
+    /// adapter.
+    fn filtered<F: Filter<T>>(self, filter: F) -> Filtered<Self, F> {
+        Filtered { iter: self, filter }
+    }
+
+    /// Filters all items with the given filter, which is borrowed by the
+    /// adapter. The filter keeps its state after the adapter is dropped.
+    fn filtered_by<F: Filter<T> + ?Sized>(self, filter: &mut F) -> FilteredBy<'_, Self, F> {
+        FilteredBy { iter: self, filter }
+    }
+}
+
+impl<T: Sample, I: Iterator<Item = T>> FilterIteratorExt<T> for I {
+    fn lowpass(
+        self,
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+    ) -> Filtered<Self, LowpassFilter<T>> {
+        self.filtered(LowpassFilter::new(sample_rate_hz, cutoff_frequency_hz))
+    }
+
+    fn try_lowpass(
+        self,
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+    ) -> Result<Filtered<Self, LowpassFilter<T>>, FilterError> {
+        let filter = LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?;
+        Ok(self.filtered(filter))
+    }
+}
+
+/// Iterator adapter that filters all items with an owned filter.
+///
+/// Created by [`FilterIteratorExt::lowpass`] and
+/// [`FilterIteratorExt::filtered`].
+///
+/// This doesn't implement [`DoubleEndedIterator`]: the filters are stateful,
+/// so the result of each item depends on all items before it. Filtering from
+/// the back would silently produce a different signal.
+#[derive(Debug, Clone)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Filtered<I, F> {
+    iter: I,
+    filter: F,
+}
+
+impl<I, F> Filtered<I, F> {
+    /// Returns the underlying iterator and the filter with its current state.
+    pub fn into_inner(self) -> (I, F) {
+        (self.iter, self.filter)
+    }
+}
+
+impl<I: Iterator, F: Filter<I::Item>> Iterator for Filtered<I, F> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|sample| self.filter.run(sample))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator, F: Filter<I::Item>> ExactSizeIterator for Filtered<I, F> {}
+
+impl<I: FusedIterator, F: Filter<I::Item>> FusedIterator for Filtered<I, F> {}
+
+/// Iterator adapter that filters all items with a borrowed filter.
+///
+/// Created by [`FilterIteratorExt::filtered_by`].
+///
+/// This doesn't implement [`DoubleEndedIterator`] for the same reason as
+/// [`Filtered`].
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct FilteredBy<'a, I, F: ?Sized> {
+    iter: I,
+    filter: &'a mut F,
+}
+
+impl<I: Iterator, F: Filter<I::Item> + ?Sized> Iterator for FilteredBy<'_, I, F> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|sample| self.filter.run(sample))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator, F: Filter<I::Item> + ?Sized> ExactSizeIterator for FilteredBy<'_, I, F> {}
+
+impl<I: FusedIterator, F: Filter<I::Item> + ?Sized> FusedIterator for FilteredBy<'_, I, F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+    use crate::{BiquadLowpassFilter, lowpass_filter_f64};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_iterator_adapters_match_lowpass_filter() {
+        let samples = sine_wave_samples(350.0, 44100.0);
+        let mut expected = samples.clone();
+        lowpass_filter_f64(&mut expected, 44100.0, 90.0);
+
+        let owned = samples.iter().copied().lowpass(44100.0, 90.0);
+        assert_eq!(owned.len(), samples.len());
+        assert_eq!(owned.collect::<Vec<_>>(), expected);
+
+        // the borrowed filter continues where it stopped
+        let mut filter = LowpassFilter::<f64>::new(44100.0, 90.0);
+        let (head, tail) = samples.split_at(1000);
+        let mut borrowed = head
+            .iter()
+            .copied()
+            .filtered_by(&mut filter)
+            .collect::<Vec<_>>();
+        borrowed.extend(tail.iter().copied().filtered_by(&mut filter));
+        assert_eq!(borrowed, expected);
+
+        let f32_samples = [0.0_f32, 0.5, 1.0];
+        let biquad = f32_samples
+            .iter()
+            .copied()
+            .filtered(BiquadLowpassFilter::<f32>::new(44100.0, 90.0))
+            .count();
+        assert_eq!(biquad, 3);
+
+        assert!(samples.iter().copied().try_lowpass(44100.0, 0.0).is_err());
+    }
+}
@@ -24,40 +24,287 @@ SOFTWARE.
 //! Simple first-order digital lowpass filters, compatible with `no_std`. You can
 //! use it, for example, to get the low frequencies from a song.
 //!
-//! ## Difference to `biquad`
+//! ## First-order vs. second-order
 //!
-//! **⚠ TL;DR: `biquad` might be a better option in some use-cases.**
+//! This crate primarily provides a basic and simple to understand, first order
+//! lowpass filter. It rolls off with 6 dB/octave above the cutoff frequency.
+//! For a **better resolution for actually cutting of signals above the
+//! cut-off frequency while the preserved signal will be less attenuated**, use
+//! the second-order [`BiquadLowpassFilter`]. It has slightly higher
+//! computational costs but rolls off with 12 dB/octave. For even steeper
+//! slopes, [`ButterworthLowpassFilter`] supports higher orders while keeping
+//! the -3 dB point exactly at the cutoff frequency. All filters implement
+//! the [`Filter`] trait, so they can be used interchangeably, for example with
+//! [`apply_filter`].
 //!
-//! This crate provides a basic and simple to understand, first order lowpass
-//! filter. The [biquad](https://crates.io/crates/biquad) crate offers second order
-//! filters, with higher accuracy. From my testing, a lowpass filter created with
-//! `biquad` has higher computational costs as this crate, but offers a
-//! **better resolution for actually cutting of signals above the cut-off frequency
-//! while the preserved signal will be less attenuated**.
-//!
-//! So for production use-cases, please also consider using `biquad`. You can run
-//! benchmark and check your data (e.g., by plotting) to make that decision.
+//! The [biquad](https://crates.io/crates/biquad) crate offers even more second
+//! order filter types.
 //!
 //! ## Usage
 //!
-//! You can either use the [`LowpassFilter`] type to integrate the filter in
-//! iterator chains or you can use a convenient function such as
-//! [`lowpass_filter`] and [`lowpass_filter_f64`]. The first approach is more
-//! flexible.
+//! You can either use the [`LowpassFilter`] type or you can use a convenient
+//! function such as [`lowpass_filter`] and [`lowpass_filter_f64`]. The first
+//! approach is more flexible. To filter buffers, the recommended way is
+//! [`LowpassFilter::process_in_place`] or [`LowpassFilter::process_into`], as
+//! they keep the state across buffers and are the fastest.
 //!
 //! ### Example with `LowpassFilter` type
 //!
-//! See implementation of [`lowpass_filter`].
+//! ```rust
+//! use lowpass_filter::LowpassFilter;
+//!
+//! let mut filter = LowpassFilter::<f32>::new(44100.0, 120.0);
+//! // the state is kept across buffers
+//! for mut buffer in [[0.0, 0.5, 1.0, -0.25], [0.1, 0.2, 0.3, 0.4]] {
+//!     filter.process_in_place(&mut buffer);
+//! }
+//! ```
+//!
+//! ### Zero-phase filtering of recordings
+//!
+//! [`lowpass_filtfilt`] and [`lowpass_filtfilt_f64`] filter a whole buffer
+//! forward and backward, so the low frequencies are extracted without phase
+//! lag and without transients at the edges.
+//!
+//! ```rust
+//! use lowpass_filter::lowpass_filtfilt;
+//!
+//! let mut recording = [0.0_f32, 0.5, 1.0, -0.25, 0.3, 0.1];
+//! lowpass_filtfilt(&mut recording, 44100.0, 120.0);
+//! ```
+//!
+//! ### Initial state
+//!
+//! By default, [`LowpassFilter`] starts from zero, so the output fades in.
+//! With [`InitialState::FirstSample`], it is primed to the first sample
+//! instead, which avoids the transient at the start of a file.
+//! [`LowpassFilter::reset_to`] primes it to an explicit value.
+//!
+//! ```rust
+//! use lowpass_filter::{InitialState, LowpassFilter};
+//!
+//! let mut filter =
+//!     LowpassFilter::<f32>::new(44100.0, 120.0).with_initial_state(InitialState::FirstSample);
+//! assert_eq!(filter.run(0.5), 0.5);
+//! ```
+//!
+//! ### Frequency response
+//!
+//! [`LowpassFilter::magnitude_at`], [`LowpassFilter::phase_at`], and
+//! [`LowpassFilter::group_delay_at`] evaluate the behavior of the filter
+//! analytically, without measuring it. [`LowpassFilter::cutoff_frequency_3db_hz`]
+//! returns the actual -3 dB point.
+//!
+//! ```rust
+//! use lowpass_filter::LowpassFilter;
+//!
+//! let filter = LowpassFilter::<f64>::new(44100.0, 1000.0);
+//! let attenuation = filter.magnitude_at(5000.0);
+//! let cutoff = filter.cutoff_frequency_3db_hz().unwrap();
+//! ```
+//!
+//! ### Example with audio callbacks
+//!
+//! [`LowpassStream`] keeps the filter state across callbacks of any length
+//! and copes with sample-rate changes between them.
+//!
+//! ```rust
+//! use lowpass_filter::LowpassStream;
+//!
+//! let mut stream = LowpassStream::<f32>::new(44100.0, 120.0);
+//! // in the audio callback
+//! let mut block = [0.0, 0.5, 1.0, -0.25];
+//! stream.process_with_sample_rate(&mut block, 44100.0).unwrap();
+//! ```
+//!
+//! ### Example with iterator chains
+//!
+//! [`FilterIteratorExt`] adds adapters to all iterators over samples.
+//!
+//! ```rust
+//! use lowpass_filter::FilterIteratorExt;
+//!
+//! let samples = [0.0_f32, 0.5, 1.0, -0.25];
+//! let filtered = samples
+//!     .iter()
+//!     .copied()
+//!     .lowpass(44100.0, 120.0)
+//!     .collect::<Vec<_>>();
+//! ```
+//!
+//! ### Alternative constructors
+//!
+//! Besides the cutoff frequency, a [`LowpassFilter`] can be described by its
+//! time constant ([`LowpassFilter::from_time_constant`]), rise time
+//! ([`LowpassFilter::from_rise_time`]), half-life
+//! ([`LowpassFilter::from_half_life_samples`]), EMA period
+//! ([`LowpassFilter::from_ema_period`]), or coefficient
+//! ([`LowpassFilter::from_alpha`]). [`LowpassFilter::cutoff_frequency_hz`],
+//! [`LowpassFilter::time_constant_s`], and [`LowpassFilter::alpha`] report the
+//! equivalent values of any filter.
+//!
+//! ```rust
+//! use lowpass_filter::LowpassFilter;
+//!
+//! let ema = LowpassFilter::<f64>::from_ema_period(1.0, 20.0);
+//! let cutoff = ema.cutoff_frequency_hz();
+//! let tau = ema.time_constant_s();
+//! ```
+//!
+//! ### Irregular timestamps
+//!
+//! [`LowpassFilter::run_with_dt`] takes the time since the previous sample
+//! instead of assuming `1 / sample_rate`. [`TimestampedLowpassFilter`]
+//! derives it from absolute timestamps, e.g., for telemetry with jitter.
+//!
+//! ```rust
+//! use lowpass_filter::TimestampedLowpassFilter;
+//!
+//! let mut filter = TimestampedLowpassFilter::<f64>::new(100.0, 5.0);
+//! for (value, timestamp_s) in [(0.1, 0.000), (0.2, 0.013), (0.3, 0.018)] {
+//!     let smoothed = filter.run_at(value, timestamp_s);
+//! }
+//! ```
 //!
 //! ### Example with `lowpass_filter` function
-//! ```rust,no_run
+//! ```rust
 //! use lowpass_filter::lowpass_filter;
 //!
 //! // some samples
-//! let mut mono_audio_data = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0];
+//! let mut mono_audio_data = [0.0, 0.5, -0.25, 1.0, -1.0, 0.125];
 //! // mutates the input buffer
 //! lowpass_filter(&mut mono_audio_data, 44100.0, 120.0);
 //! ```
+//!
+//! ### Integer PCM samples
+//!
+//! The filters operate on normalized floats. The [`sample`] module converts
+//! `u8`, `i8`, `i16`, packed 24-bit, and `i32` samples to and from `f32` and
+//! `f64`, and filters integer buffers in-place.
+//!
+//! ```rust
+//! use lowpass_filter::LowpassFilter;
+//! use lowpass_filter::sample::{Scaling, filter_pcm_in_place};
+//!
+//! let mut pcm = [0_i16, 8000, 16000, 24000];
+//! let mut filter = LowpassFilter::<f32>::new(44100.0, 120.0);
+//! filter_pcm_in_place(&mut filter, &mut pcm, Scaling::Symmetric);
+//! ```
+//!
+//! ### Signal analysis
+//!
+//! The [`analysis`] module measures signals, e.g., to verify the effect of a
+//! filter on your own data: RMS, mean power, energy, peak, crest factor,
+//! dBFS conversions, and the attenuation between two signals.
+//!
+//! ```rust
+//! use lowpass_filter::analysis::attenuation_db;
+//! use lowpass_filter::LowpassFilter;
+//!
+//! let before = [0.5_f32, -0.5, 0.5, -0.5, 0.5, -0.5, 0.5, -0.5];
+//! let mut after = before;
+//! LowpassFilter::new(44100.0, 1000.0).process_in_place(&mut after);
+//! assert!(attenuation_db(&before, &after) > 10.0);
+//! ```
+//!
+//! ### Data outside of `-1.0..=1.0`
+//!
+//! By default, the filters expect samples in range `-1.0..=1.0`, as usual
+//! for audio. Inputs are checked in debug builds and outputs are clamped. A
+//! [`RangePolicy`] changes this for [`LowpassFilter`]: [`Unbounded`] for
+//! arbitrary data, such as ADC counts or temperatures, [`Normalized`] (the
+//! default), or a [`CustomRange`].
+//!
+//! ```rust
+//! use lowpass_filter::{LowpassFilter, Unbounded};
+//!
+//! let mut data = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0];
+//! let mut filter = LowpassFilter::new(44100.0, 120.0).with_range_policy(Unbounded);
+//! filter.process_in_place(&mut data);
+//! ```
+//!
+//! ### Multichannel audio
+//!
+//! [`MultiChannelLowpassFilter`] keeps an independent state per channel and
+//! filters interleaved or planar buffers in-place, so stereo or 5.1 material
+//! doesn't need to be downmixed to mono first.
+//!
+//! ### Highpass filter
+//!
+//! [`HighpassFilter`], [`highpass_filter`], and [`highpass_filter_f64`] are
+//! the counterparts of the lowpass filter API and remove the frequencies below
+//! the cutoff frequency instead.
+//!
+//! ### Band-pass and band-stop filter
+//!
+//! [`BandpassFilter`] only keeps the frequencies between two cutoff
+//! frequencies, for example, the bass band of a kick drum. [`BandstopFilter`]
+//! removes them.
+//!
+//! ```rust
+//! use lowpass_filter::BandpassFilter;
+//!
+//! let mut filter = BandpassFilter::<f32>::new(44100.0, 60.0, 250.0);
+//! let _filtered = filter.run(0.5);
+//! ```
+//!
+//! ### Generic sample types
+//!
+//! All filters and functions are generic over the [`Sample`] trait, which is
+//! implemented for `f32` and `f64`. Code that is generic over the float type
+//! can use `LowpassFilter<T>` with a `T: Sample` bound.
+//!
+//! ### Many channels with SIMD
+//!
+//! [`LowpassFilterBank`] filters many independent channels with the same
+//! parameters, e.g., 64 to 512 channels of a microphone array. With the `simd`
+//! feature, multiple channels are processed at the same time. The results are
+//! bit-identical to [`LowpassFilter::run`].
+//!
+//! ```rust
+//! use lowpass_filter::LowpassFilterBank;
+//!
+//! let mut bank = LowpassFilterBank::<f32, 256>::new(44100.0, 120.0);
+//! let frame = bank.run([0.25; 256]);
+//! ```
+//!
+//! ### Fixed-point filters
+//!
+//! On microcontrollers without an FPU, [`LowpassFilterQ15`] and
+//! [`LowpassFilterQ31`] filter `i16` and `i32` samples with integer
+//! arithmetic only.
+//!
+//! ### Fallible construction
+//!
+//! If the sample rate or cutoff frequency are only known at runtime, use
+//! [`LowpassFilter::try_new`], [`try_lowpass_filter`], or
+//! [`try_lowpass_filter_f64`]. They return a [`FilterError`] instead of
+//! panicking.
+//!
+//! ```rust
+//! use lowpass_filter::{FilterError, LowpassFilter};
+//!
+//! let filter = LowpassFilter::<f32>::try_new(44100.0, 30000.0);
+//! assert_eq!(filter.unwrap_err(), FilterError::NyquistViolation);
+//! ```
+//!
+//! ## Cargo Features
+//!
+//! - `num-traits`: Implements [`Sample`] for all types that implement
+//!   `num_traits::float::FloatCore`, `num_traits::ConstZero`, and
+//!   `num_traits::ConstOne`.
+//! - `simd`: Processes the channels of [`LowpassFilterBank`] with SIMD
+//!   instructions via the `wide` crate.
+//! - `libm`: Uses the math functions of the `libm` crate instead of the
+//!   built-in approximations, e.g., for the frequency response.
+//! - `serde`: Implements `Serialize` and `Deserialize` for [`LowpassFilter`],
+//!   including its live state, e.g., to checkpoint long-running pipelines.
+//!   The format is versioned and all values are validated when a filter is
+//!   deserialized.
+//! - `wav`: Adds `lowpass_wav_file` to filter WAV files via the `hound`
+//!   crate. This requires `std`.
+//! - `cli`: Builds the `lowpass` command-line tool, which filters WAV files
+//!   (`cargo install lowpass-filter --features cli`).
 
 #![deny(
     clippy::all,
@@ -79,119 +326,515 @@ SOFTWARE.
 #![no_std]
 
 #[cfg_attr(test, macro_use)]
-#[cfg(test)]
+#[cfg(any(test, feature = "wav"))]
 extern crate std;
 
-use core::ops::RangeInclusive;
+pub mod analysis;
+mod band;
+mod bank;
+mod biquad;
+mod butterworth;
+mod constructors;
+mod error;
+mod filtfilt;
+mod fixed;
+mod highpass;
+mod initial;
+mod irregular;
+mod iter;
+mod math;
+mod multichannel;
+mod range;
+mod response;
+pub mod sample;
+#[cfg(feature = "serde")]
+mod serialization;
+mod stream;
+#[cfg(feature = "wav")]
+mod wav;
+
+pub use band::{BandpassFilter, BandstopFilter};
+pub use bank::{LowpassFilterBank, SimdSample};
+pub use biquad::BiquadLowpassFilter;
+pub use butterworth::ButterworthLowpassFilter;
+pub use error::FilterError;
+pub use filtfilt::{
+    lowpass_filtfilt, lowpass_filtfilt_f64, try_lowpass_filtfilt, try_lowpass_filtfilt_f64,
+};
+pub use fixed::{LowpassFilterQ15, LowpassFilterQ31};
+pub use highpass::{
+    HighpassFilter, highpass_filter, highpass_filter_f64, try_highpass_filter,
+    try_highpass_filter_f64,
+};
+pub use initial::InitialState;
+pub use irregular::TimestampedLowpassFilter;
+pub use iter::{FilterIteratorExt, Filtered, FilteredBy};
+pub use multichannel::MultiChannelLowpassFilter;
+pub use range::{CustomRange, Normalized, RangePolicy, Unbounded};
+pub use sample::Sample;
+pub use stream::LowpassStream;
+#[cfg(feature = "wav")]
+pub use wav::{
+    ChannelMode, WavError, WavProgress, check_wav_file, filter_wav_file,
+    filter_wav_file_with_progress, lowpass_wav_file,
+};
 
-/// A single-order lowpass filter with single precision that consumes and emits
-/// items one by one.
+use core::f64::consts::PI;
+
+/// Common interface of all filters of this crate that consume and emit items
+/// one by one.
 ///
-/// It is mandatory to operate on f32 values in range `-1.0..=1.0`, which is
-/// also the default in DSP.
+/// It enables code that doesn't care about the concrete filter, such as
+/// [`apply_filter`]. Each filter also offers `run` and `reset` as inherent
+/// methods, so this trait doesn't need to be in scope for regular use.
+pub trait Filter<T> {
+    /// Filter a single sample and return the filtered result.
+    fn run(&mut self, input: T) -> T;
+
+    /// Reset the internal filter state.
+    fn reset(&mut self);
+}
+
+/// Applies a [`Filter`] to the data provided in the mutable buffer and
+/// changes the items in-place.
+///
+/// This is the generic counterpart of [`lowpass_filter`]. The state of the
+/// filter is kept, so it can be called multiple times for consecutive
+/// buffers.
+///
+/// # Arguments
+/// - `filter`: Any filter of this crate, such as [`BiquadLowpassFilter`].
+/// - `sample_iter`: Iterator over the samples. This can also be a
+///   `[1.0, ...]`-style slice
+#[inline]
+pub fn apply_filter<'a, T: Copy + 'a, F: Filter<T> + ?Sized, I: IntoIterator<Item = &'a mut T>>(
+    filter: &mut F,
+    sample_iter: I,
+) {
+    for sample in sample_iter.into_iter() {
+        *sample = filter.run(*sample);
+    }
+}
+
+/// A single-order lowpass filter that consumes and emits items one by one.
+///
+/// By default, it is mandatory to operate on values in range `-1.0..=1.0`,
+/// which is also the default in DSP. Other ranges can be configured with
+/// [`LowpassFilter::with_range_policy`], see [`RangePolicy`]. The filter works
+/// with all [`Sample`] types, such as `f32` and `f64`.
+///
+/// The cutoff frequency and the sample rate can be changed at runtime with
+/// [`LowpassFilter::set_cutoff`] and [`LowpassFilter::set_sample_rate`]
+/// without losing the filter state. To prevent zipper noise when the cutoff
+/// frequency is swept, the coefficient can be smoothed with
+/// [`LowpassFilter::set_coefficient_smoothing`].
+///
+/// The state before the first sample is defined by the [`InitialState`],
+/// which is zero by default.
 ///
 /// # More Info
 /// - <https://en.wikipedia.org/wiki/Low-pass_filter#Simple_infinite_impulse_response_filter>
 #[derive(Debug, Clone)]
-pub struct LowpassFilter<T> {
+pub struct LowpassFilter<T, R = Normalized> {
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+    /// The coefficient that is currently in use.
     alpha: T,
+    /// The coefficient that belongs to the current parameters. `alpha`
+    /// follows it if coefficient smoothing is active.
+    alpha_target: T,
+    /// Smoothing time constant for the coefficient in seconds.
+    smoothing_time_s: T,
+    /// Per-sample factor by which `alpha` approaches `alpha_target`. `1.0`
+    /// means no smoothing.
+    smoothing_factor: T,
     prev: T,
+    /// Whether the filter is primed with the next sample. Only used with
+    /// [`InitialState::FirstSample`].
     next_is_first: bool,
+    initial_state: InitialState<T>,
+    range: R,
 }
 
-macro_rules! impl_lowpass_filter {
-    ($t:ty, $pi:expr) => {
-        impl LowpassFilter<$t> {
-            /// Create a new lowpass filter.
-            ///
-            /// # Arguments
-            /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
-            /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
-            #[must_use]
-            pub fn new(sample_rate_hz: $t, cutoff_frequency_hz: $t) -> Self {
-                // Nyquist rule
-                assert!(cutoff_frequency_hz * 2.0 <= sample_rate_hz);
-
-                let rc = 1.0 / (cutoff_frequency_hz * 2.0 * $pi);
-                let dt = 1.0 / sample_rate_hz;
-                let alpha = dt / (rc + dt);
-
-                Self {
-                    alpha,
-                    prev: 0.0,
-                    next_is_first: true,
-                }
-            }
+impl<T: Sample> LowpassFilter<T> {
+    /// Create a new lowpass filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, cutoff_frequency_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
 
-            /// Filter a single sample and return the filtered result.
-            ///
-            /// It is mandatory to operate on f32 values in range
-            /// `-1.0..=1.0`, which is also the default in DSP. The returned
-            /// value is also guaranteed to be in that range.
-            #[inline]
-            pub fn run(&mut self, input: $t) -> $t {
-                const RANGE: RangeInclusive<$t> = -1.0..=1.0;
-                debug_assert!(
-                    RANGE.contains(&input),
-                    "samples must be in range {RANGE:?}: {input}"
-                );
-
-                let value = if self.next_is_first {
-                    self.next_is_first = false;
-                    self.prev = input;
-                    input * self.alpha
-                } else {
-                    self.prev = self.prev + self.alpha * (input - self.prev);
-                    self.prev
-                };
-
-                // very small deviations caused by floating point operations
-                // are tolerable; just truncate the value
-                value.clamp(-1.0, 1.0)
-            }
+    /// Create a new lowpass filter or return an error if the
+    /// parameters are invalid.
+    ///
+    /// The sample rate and the cutoff frequency must be finite and
+    /// greater than zero. The cutoff frequency must not be above
+    /// the Nyquist frequency (`sample_rate_hz / 2`).
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        FilterError::check_frequencies(sample_rate_hz.to_f64(), cutoff_frequency_hz.to_f64())?;
+
+        let alpha = Self::calc_alpha(sample_rate_hz, cutoff_frequency_hz);
+        Ok(Self::from_validated(
+            sample_rate_hz,
+            cutoff_frequency_hz,
+            alpha,
+        ))
+    }
+
+    /// Creates a filter with the default state from validated parameters
+    /// and the coefficient that belongs to them.
+    pub(crate) const fn from_validated(
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+        alpha: T,
+    ) -> Self {
+        Self {
+            sample_rate_hz,
+            cutoff_frequency_hz,
+            alpha,
+            alpha_target: alpha,
+            smoothing_time_s: T::ZERO,
+            smoothing_factor: T::ONE,
+            prev: T::ZERO,
+            next_is_first: false,
+            initial_state: InitialState::Zero,
+            range: Normalized,
+        }
+    }
+}
 
-            /// Reset the internal filter state.
-            pub const fn reset(&mut self) {
-                self.prev = 0.0;
+impl<T: Sample, R: RangePolicy<T>> LowpassFilter<T, R> {
+    /// Replaces the range policy, which defines the values that the filter
+    /// accepts and emits. The parameters and the state are kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lowpass_filter::{LowpassFilter, Unbounded};
+    ///
+    /// // e.g., readings of a temperature sensor
+    /// let mut filter = LowpassFilter::<f32>::new(10.0, 0.5).with_range_policy(Unbounded);
+    /// let _filtered = filter.run(21.5);
+    /// ```
+    #[must_use]
+    pub const fn with_range_policy<R2: RangePolicy<T>>(self, range: R2) -> LowpassFilter<T, R2> {
+        LowpassFilter {
+            sample_rate_hz: self.sample_rate_hz,
+            cutoff_frequency_hz: self.cutoff_frequency_hz,
+            alpha: self.alpha,
+            alpha_target: self.alpha_target,
+            smoothing_time_s: self.smoothing_time_s,
+            smoothing_factor: self.smoothing_factor,
+            prev: self.prev,
+            next_is_first: self.next_is_first,
+            initial_state: self.initial_state,
+            range,
+        }
+    }
+
+    /// Returns the range policy.
+    #[must_use]
+    pub const fn range_policy(&self) -> &R {
+        &self.range
+    }
+
+    /// Replaces the [`InitialState`], which defines the state before the
+    /// first sample and after [`Self::reset`]. The state is reset
+    /// accordingly.
+    #[must_use]
+    pub const fn with_initial_state(mut self, initial_state: InitialState<T>) -> Self {
+        self.initial_state = initial_state;
+        self.reset();
+        self
+    }
+
+    /// Returns the [`InitialState`].
+    #[must_use]
+    pub const fn initial_state(&self) -> InitialState<T> {
+        self.initial_state
+    }
+
+    /// Calculates the coefficient from validated parameters.
+    fn calc_alpha(sample_rate_hz: T, cutoff_frequency_hz: T) -> T {
+        let rc = T::ONE / (cutoff_frequency_hz * T::from_f64(2.0) * T::from_f64(PI));
+        let dt = T::ONE / sample_rate_hz;
+        dt / (rc + dt)
+    }
+
+    /// Calculates the per-sample factor of the coefficient smoothing.
+    fn calc_smoothing_factor(sample_rate_hz: T, smoothing_time_s: T) -> T {
+        let dt = T::ONE / sample_rate_hz;
+        dt / (smoothing_time_s + dt)
+    }
+
+    /// Returns the sample rate in Hz.
+    #[must_use]
+    pub const fn sample_rate_hz(&self) -> T {
+        self.sample_rate_hz
+    }
+
+    /// Returns the cutoff frequency in Hz.
+    ///
+    /// If coefficient smoothing is active, the filter might still be
+    /// on its way to this cutoff frequency.
+    #[must_use]
+    pub const fn cutoff_frequency_hz(&self) -> T {
+        self.cutoff_frequency_hz
+    }
+
+    /// Changes the cutoff frequency while keeping the filter state.
+    ///
+    /// # Panics
+    /// If the cutoff frequency is invalid. See
+    /// [`Self::try_set_cutoff`] for the fallible variant.
+    pub fn set_cutoff(&mut self, cutoff_frequency_hz: T) {
+        if let Err(e) = self.try_set_cutoff(cutoff_frequency_hz) {
+            panic!("invalid cutoff frequency: {e}");
+        }
+    }
+
+    /// Changes the cutoff frequency while keeping the filter state or
+    /// returns an error if it is invalid. The filter is not modified
+    /// in that case.
+    ///
+    /// The new coefficient takes effect immediately, unless
+    /// coefficient smoothing is active.
+    pub fn try_set_cutoff(&mut self, cutoff_frequency_hz: T) -> Result<(), FilterError> {
+        self.try_set_frequencies(self.sample_rate_hz, cutoff_frequency_hz)
+    }
+
+    /// Changes the sample rate while keeping the filter state.
+    ///
+    /// # Panics
+    /// If the sample rate is invalid. See
+    /// [`Self::try_set_sample_rate`] for the fallible variant.
+    pub fn set_sample_rate(&mut self, sample_rate_hz: T) {
+        if let Err(e) = self.try_set_sample_rate(sample_rate_hz) {
+            panic!("invalid sample rate: {e}");
+        }
+    }
+
+    /// Changes the sample rate while keeping the filter state or
+    /// returns an error if it is invalid, e.g., if the cutoff
+    /// frequency would be above the new Nyquist frequency. The filter
+    /// is not modified in that case.
+    ///
+    /// The new coefficient takes effect immediately, unless
+    /// coefficient smoothing is active.
+    pub fn try_set_sample_rate(&mut self, sample_rate_hz: T) -> Result<(), FilterError> {
+        self.try_set_frequencies(sample_rate_hz, self.cutoff_frequency_hz)
+    }
+
+    fn try_set_frequencies(
+        &mut self,
+        sample_rate_hz: T,
+        cutoff_frequency_hz: T,
+    ) -> Result<(), FilterError> {
+        FilterError::check_frequencies(sample_rate_hz.to_f64(), cutoff_frequency_hz.to_f64())?;
+
+        self.sample_rate_hz = sample_rate_hz;
+        self.cutoff_frequency_hz = cutoff_frequency_hz;
+        self.alpha_target = Self::calc_alpha(sample_rate_hz, cutoff_frequency_hz);
+        self.smoothing_factor = Self::calc_smoothing_factor(sample_rate_hz, self.smoothing_time_s);
+        if self.smoothing_time_s == T::ZERO {
+            self.alpha = self.alpha_target;
+        }
+        Ok(())
+    }
+
+    /// Enables smoothing of the filter coefficient after the cutoff
+    /// frequency or the sample rate were changed.
+    ///
+    /// Instead of jumping to the new value, the coefficient follows it
+    /// with a first-order lag of the given time constant. This
+    /// prevents zipper noise when the cutoff frequency is changed
+    /// while audio is running. A value of `0.0` disables the
+    /// smoothing, which is the default.
+    ///
+    /// # Arguments
+    /// - `smoothing_time_s`: Time constant in seconds (e.g., 0.01).
+    ///
+    /// # Panics
+    /// If the time is negative, NaN, or infinite. See
+    /// [`Self::try_set_coefficient_smoothing`] for the fallible
+    /// variant.
+    pub fn set_coefficient_smoothing(&mut self, smoothing_time_s: T) {
+        if let Err(e) = self.try_set_coefficient_smoothing(smoothing_time_s) {
+            panic!("invalid smoothing time: {e}");
+        }
+    }
+
+    /// Fallible variant of [`Self::set_coefficient_smoothing`].
+    pub fn try_set_coefficient_smoothing(
+        &mut self,
+        smoothing_time_s: T,
+    ) -> Result<(), FilterError> {
+        if !smoothing_time_s.is_finite() || smoothing_time_s < T::ZERO {
+            return Err(FilterError::InvalidSmoothingTime);
+        }
+
+        self.smoothing_time_s = smoothing_time_s;
+        self.smoothing_factor = Self::calc_smoothing_factor(self.sample_rate_hz, smoothing_time_s);
+        Ok(())
+    }
+
+    /// Filter a single sample and return the filtered result.
+    ///
+    /// The [`RangePolicy`] of the filter checks the input (in debug builds)
+    /// and limits the returned value. With the default [`Normalized`]
+    /// policy, the input must be in range `-1.0..=1.0` and the returned
+    /// value is guaranteed to be in that range, too.
+    #[inline]
+    pub fn run(&mut self, input: T) -> T {
+        self.range.check_input(input);
+
+        if self.alpha != self.alpha_target {
+            let alpha = self.alpha + self.smoothing_factor * (self.alpha_target - self.alpha);
+            // Snap to the target once the steps are too small to
+            // make progress.
+            self.alpha = if alpha == self.alpha {
+                self.alpha_target
+            } else {
+                alpha
+            };
+        }
+
+        if self.next_is_first {
+            self.next_is_first = false;
+            self.prev = input;
+        } else {
+            self.prev = self.prev + self.alpha * (input - self.prev);
+        }
+
+        self.range.limit(self.prev)
+    }
+
+    /// Filters a buffer of samples in-place. The state is kept across
+    /// calls, so a stream can be filtered buffer by buffer.
+    ///
+    /// This is the recommended way to filter buffers. The results are the
+    /// same as calling [`Self::run`] for each sample, but the inner loop is
+    /// tight and can be unrolled by the compiler. Unlike [`Self::run`], the
+    /// range of the input samples is not checked in debug builds.
+    pub fn process_in_place(&mut self, samples: &mut [T]) {
+        let mut samples = samples;
+        // The special cases are handled by `run`: the first sample and a
+        // coefficient that is still being smoothed.
+        while self.next_is_first || self.alpha != self.alpha_target {
+            let Some((sample, rest)) = samples.split_first_mut() else {
+                return;
+            };
+            *sample = self.run(*sample);
+            samples = rest;
+        }
+
+        let alpha = self.alpha;
+        let range = self.range;
+        let mut prev = self.prev;
+        for sample in samples {
+            prev = prev + alpha * (*sample - prev);
+            *sample = range.limit(prev);
+        }
+        self.prev = prev;
+    }
+
+    /// Filters the samples of `input` and writes them to `output`. The state
+    /// is kept across calls.
+    ///
+    /// See [`Self::process_in_place`].
+    ///
+    /// # Panics
+    /// If `input` and `output` have different lengths.
+    pub fn process_into(&mut self, input: &[T], output: &mut [T]) {
+        assert_eq!(
+            input.len(),
+            output.len(),
+            "input and output must have the same length"
+        );
+        output.copy_from_slice(input);
+        self.process_in_place(output);
+    }
+
+    /// Reset the internal filter state according to the [`InitialState`].
+    ///
+    /// The parameters are kept. A coefficient that is still being
+    /// smoothed jumps to its target.
+    pub const fn reset(&mut self) {
+        match self.initial_state {
+            InitialState::Zero => self.reset_to(T::ZERO),
+            InitialState::Value(value) => self.reset_to(value),
+            InitialState::FirstSample => {
+                self.reset_to(T::ZERO);
                 self.next_is_first = true;
             }
         }
-    };
+    }
+
+    /// Reset the internal filter state, so that it is primed to the given
+    /// value, as if it had seen this value forever. The next output
+    /// approaches the next input from this value.
+    ///
+    /// The parameters are kept. A coefficient that is still being
+    /// smoothed jumps to its target.
+    pub const fn reset_to(&mut self, value: T) {
+        self.alpha = self.alpha_target;
+        self.prev = value;
+        self.next_is_first = false;
+    }
 }
 
-impl_lowpass_filter!(f32, core::f32::consts::PI);
-impl_lowpass_filter!(f64, core::f64::consts::PI);
+impl<T: Sample, R: RangePolicy<T>> Filter<T> for LowpassFilter<T, R> {
+    #[inline]
+    fn run(&mut self, input: T) -> T {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
 
 /// Applies a [`LowpassFilter`] to the data provided in the mutable buffer and
 /// changes the items in-place.
 ///
-/// It is mandatory to operate on f32 values in range `-1.0..=1.0`, which is
-/// also the default in DSP.
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP. The function works with all [`Sample`] types,
+/// such as `f32` and `f64`.
 ///
 /// # Arguments
 /// - `sample_iter`: Iterator over the samples. This can also be a
 ///   `[1.0, ...]`-style slice
 /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
 /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_lowpass_filter`] for the fallible
+/// variant.
 #[inline]
-pub fn lowpass_filter<'a, I: IntoIterator<Item = &'a mut f32>>(
+pub fn lowpass_filter<'a, T: Sample + 'a, I: IntoIterator<Item = &'a mut T>>(
     sample_iter: I,
-    sample_rate_hz: f32,
-    cutoff_frequency_hz: f32,
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
 ) {
-    let mut filter = LowpassFilter::<f32>::new(sample_rate_hz, cutoff_frequency_hz);
-
-    for sample in sample_iter.into_iter() {
-        let new_sample = filter.run(*sample);
-        *sample = new_sample;
-    }
+    let mut filter = LowpassFilter::new(sample_rate_hz, cutoff_frequency_hz);
+    apply_filter(&mut filter, sample_iter);
 }
 
-/// Applies a [`LowpassFilter`] to the data provided in the mutable buffer and
-/// changes the items in-place.
-///
-/// It is mandatory to operate on f32 values in range `-1.0..=1.0`, which is
-/// also the default in DSP.
+/// Fallible variant of [`lowpass_filter`]. The buffer is not touched if the
+/// parameters are invalid.
 ///
 /// # Arguments
 /// - `sample_iter`: Iterator over the samples. This can also be a
@@ -199,17 +842,38 @@ pub fn lowpass_filter<'a, I: IntoIterator<Item = &'a mut f32>>(
 /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
 /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
 #[inline]
+pub fn try_lowpass_filter<'a, T: Sample + 'a, I: IntoIterator<Item = &'a mut T>>(
+    sample_iter: I,
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+) -> Result<(), FilterError> {
+    let mut filter = LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?;
+    apply_filter(&mut filter, sample_iter);
+    Ok(())
+}
+
+/// Same as [`lowpass_filter`] but restricted to `f64`.
+///
+/// # Panics
+/// If the parameters are invalid. See [`try_lowpass_filter_f64`] for the
+/// fallible variant.
+#[inline]
 pub fn lowpass_filter_f64<'a, I: IntoIterator<Item = &'a mut f64>>(
     sample_iter: I,
     sample_rate_hz: f64,
     cutoff_frequency_hz: f64,
 ) {
-    let mut filter = LowpassFilter::<f64>::new(sample_rate_hz, cutoff_frequency_hz);
+    lowpass_filter(sample_iter, sample_rate_hz, cutoff_frequency_hz);
+}
 
-    for sample in sample_iter.into_iter() {
-        let new_sample = filter.run(*sample);
-        *sample = new_sample;
-    }
+/// Same as [`try_lowpass_filter`] but restricted to `f64`.
+#[inline]
+pub fn try_lowpass_filter_f64<'a, I: IntoIterator<Item = &'a mut f64>>(
+    sample_iter: I,
+    sample_rate_hz: f64,
+    cutoff_frequency_hz: f64,
+) -> Result<(), FilterError> {
+    try_lowpass_filter(sample_iter, sample_rate_hz, cutoff_frequency_hz)
 }
 
 #[cfg(test)]
@@ -218,7 +882,8 @@ mod test_util;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_util::{calculate_power, sine_wave_samples, target_dir_test_artifacts};
+    use crate::analysis::energy;
+    use crate::test_util::{sine_wave_samples, target_dir_test_artifacts};
     use audio_visualizer::Channels;
     use audio_visualizer::waveform::plotters_png_file::waveform_static_plotters_png_visualize;
     use std::vec::Vec;
@@ -244,14 +909,14 @@ mod tests {
         let mut samples_l_lowpassed = samples_l_orig.clone();
         let mut samples_h_lowpassed = samples_h_orig.clone();
 
-        let power_l_orig = calculate_power(&samples_l_orig);
-        let power_h_orig = calculate_power(&samples_h_orig);
+        let power_l_orig = energy(&samples_l_orig);
+        let power_h_orig = energy(&samples_h_orig);
 
         lowpass_filter_f64(samples_l_lowpassed.as_mut_slice(), 44100.0, 90.0);
         lowpass_filter_f64(samples_h_lowpassed.as_mut_slice(), 44100.0, 90.0);
 
-        let power_l_lowpassed = calculate_power(&samples_l_lowpassed);
-        let power_h_lowpassed = calculate_power(&samples_h_lowpassed);
+        let power_l_lowpassed = energy(&samples_l_lowpassed);
+        let power_h_lowpassed = energy(&samples_h_lowpassed);
 
         waveform_static_plotters_png_visualize(
             &samples_l_lowpassed
@@ -293,10 +958,161 @@ mod tests {
         lowpass_filter(lowpassed_f32.as_mut_slice(), 44100.0, 90.0);
         lowpass_filter_f64(lowpassed_f64.as_mut_slice(), 44100.0, 90.0);
 
-        let power_f32 =
-            calculate_power(&lowpassed_f32.iter().map(|x| *x as f64).collect::<Vec<_>>());
-        let power_f64 = calculate_power(&lowpassed_f64);
+        let power_f32 = f64::from(analysis::mean_power(&lowpassed_f32));
+        let power_f64 = analysis::mean_power(&lowpassed_f64);
+
+        assert!((power_f32 - power_f64).abs() <= 1e-5 * power_f64);
+    }
+
+    #[test]
+    fn test_try_new_rejects_invalid_parameters() {
+        assert!(LowpassFilter::<f32>::try_new(44100.0, 120.0).is_ok());
+        assert!(LowpassFilter::<f64>::try_new(44100.0, 22050.0).is_ok());
+
+        for sample_rate in [0.0, -44100.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                LowpassFilter::<f64>::try_new(sample_rate, 120.0).unwrap_err(),
+                FilterError::InvalidSampleRate
+            );
+        }
+        for cutoff in [0.0, -120.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                LowpassFilter::<f32>::try_new(44100.0, cutoff).unwrap_err(),
+                FilterError::InvalidCutoffFrequency
+            );
+        }
+        assert_eq!(
+            LowpassFilter::<f32>::try_new(44100.0, 22051.0).unwrap_err(),
+            FilterError::NyquistViolation
+        );
+
+        let mut samples = [0.5_f64, 0.25];
+        assert_eq!(
+            try_lowpass_filter_f64(&mut samples, 44100.0, 0.0),
+            Err(FilterError::InvalidCutoffFrequency)
+        );
+        assert_eq!(samples, [0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_set_cutoff_keeps_state() {
+        let mut retuned = LowpassFilter::<f64>::new(44100.0, 100.0);
+        for _ in 0..100 {
+            retuned.run(0.5);
+        }
+        retuned.set_cutoff(1000.0);
+        assert_eq!(retuned.cutoff_frequency_hz(), 1000.0);
+
+        // equivalent filter that had the new cutoff frequency from the
+        // beginning but the same state
+        let mut reference = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        reference.reset_to(retuned.prev);
+        for _ in 0..100 {
+            assert_eq!(retuned.run(-0.5), reference.run(-0.5));
+        }
+
+        assert_eq!(
+            retuned.try_set_sample_rate(1000.0),
+            Err(FilterError::NyquistViolation)
+        );
+        assert_eq!(retuned.sample_rate_hz(), 44100.0);
+    }
+
+    #[test]
+    fn test_coefficient_smoothing() {
+        let mut filter = LowpassFilter::<f32>::new(44100.0, 100.0);
+        filter.set_coefficient_smoothing(0.01);
+        let alpha_start = filter.alpha;
+        filter.set_cutoff(5000.0);
+        let alpha_end = filter.alpha_target;
+
+        // the coefficient moves monotonically towards the target
+        let mut last = alpha_start;
+        for _ in 0..441 {
+            filter.run(0.0);
+            assert!(filter.alpha > last || filter.alpha == alpha_end);
+            last = filter.alpha;
+        }
+        // ~63% after one time constant
+        let progress = (filter.alpha - alpha_start) / (alpha_end - alpha_start);
+        assert!((progress - 0.63).abs() < 0.01, "{progress}");
+
+        for _ in 0..44100 {
+            filter.run(0.0);
+        }
+        assert_eq!(filter.alpha, alpha_end);
+
+        assert_eq!(
+            filter.try_set_coefficient_smoothing(-1.0),
+            Err(FilterError::InvalidSmoothingTime)
+        );
+    }
+
+    #[test]
+    fn test_block_processing_matches_run() {
+        let samples = sine_wave_samples(120.0, 44100.0);
+        let mut reference = LowpassFilter::<f64>::new(44100.0, 90.0);
+        let mut filter = reference.clone();
+        reference.set_coefficient_smoothing(0.01);
+        filter.set_coefficient_smoothing(0.01);
+
+        let expected = samples
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                // retune in the middle of the signal
+                if i == 1000 {
+                    reference.set_cutoff(500.0);
+                }
+                reference.run(*sample)
+            })
+            .collect::<Vec<_>>();
+
+        let mut actual = vec![0.0; samples.len()];
+        let (input_a, input_b) = samples.split_at(1000);
+        let (output_a, output_b) = actual.split_at_mut(1000);
+        filter.process_into(input_a, output_a);
+        filter.set_cutoff(500.0);
+        // odd chunk sizes, so chunks start during and after the smoothing
+        for (input, output) in input_b.chunks(333).zip(output_b.chunks_mut(333)) {
+            filter.process_into(input, output);
+        }
+        assert_eq!(actual, expected);
+
+        filter.reset();
+        reference.reset();
+        let mut in_place = samples.clone();
+        filter.process_in_place(&mut in_place);
+        let expected = samples
+            .iter()
+            .map(|x| reference.run(*x))
+            .collect::<Vec<_>>();
+        assert_eq!(in_place, expected);
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let zero = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        let alpha = zero.alpha;
+        let mut zero = zero.with_initial_state(InitialState::Zero);
+        assert_eq!(zero.run(0.5), 0.5 * alpha);
+        assert_eq!(zero.run(0.5), 0.5 * alpha + alpha * (0.5 - 0.5 * alpha));
+
+        // no transient
+        let mut first_sample = LowpassFilter::<f64>::new(44100.0, 1000.0)
+            .with_initial_state(InitialState::FirstSample);
+        assert_eq!(first_sample.run(0.5), 0.5);
+        assert_eq!(first_sample.run(0.5), 0.5);
+        first_sample.reset();
+        assert_eq!(first_sample.run(-0.25), -0.25);
 
-        assert!((power_f32 - power_f64).abs() <= 0.00024);
+        let mut value = LowpassFilter::<f64>::new(44100.0, 1000.0)
+            .with_initial_state(InitialState::Value(0.25));
+        assert_eq!(value.run(0.25), 0.25);
+        value.reset_to(-0.5);
+        assert_eq!(value.run(0.5), -0.5 + alpha);
+        value.reset();
+        assert_eq!(value.run(0.25), 0.25);
+        assert_eq!(value.initial_state(), InitialState::Value(0.25));
     }
 }
@@ -0,0 +1,422 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Minimal `no_std` implementations of the math functions that are needed to
+//! calculate filter coefficients. `core` doesn't provide them.
+//!
+//! All functions operate on `f64`. They are only used when filters are
+//! created or analyzed, never in the hot path. With the `libm` feature, the
+//! functions of the `libm` crate are used instead.
+
+#[cfg(not(feature = "libm"))]
+use core::f64::consts::{FRAC_2_PI, FRAC_PI_2, LN_2, PI, SQRT_2};
+
+/// `ln(2)` split into a part with trailing zero bits, so that multiplying it
+/// with small integers is exact, and the remainder. Used for the Cody-Waite
+/// range reduction. The values are the same as in fdlibm.
+#[cfg(not(feature = "libm"))]
+const LN_2_HI: f64 = 6.931_471_803_691_238e-1;
+#[cfg(not(feature = "libm"))]
+const LN_2_LO: f64 = 1.908_214_929_270_587_7e-10;
+
+/// The part of `π/2` that is lost when it is rounded to `f64`. Used for the
+/// Cody-Waite range reduction.
+#[cfg(not(feature = "libm"))]
+const FRAC_PI_2_LO: f64 = 6.123_233_995_736_766e-17;
+
+/// Returns `(sin(x), cos(x))`.
+#[cfg(feature = "libm")]
+pub fn sin_cos(x: f64) -> (f64, f64) {
+    libm::sincos(x)
+}
+
+/// Returns the square root of `x`.
+#[cfg(feature = "libm")]
+pub fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+/// Returns the four-quadrant arctangent of `y / x` in `[-π, π]`.
+#[cfg(feature = "libm")]
+pub fn atan2(y: f64, x: f64) -> f64 {
+    libm::atan2(y, x)
+}
+
+/// Returns the arccosine of `x` in `[0, π]`.
+#[cfg(feature = "libm")]
+pub fn acos(x: f64) -> f64 {
+    libm::acos(x)
+}
+
+/// Returns `e^x - 1`, which is accurate even if `x` is close to zero.
+#[cfg(feature = "libm")]
+pub fn exp_m1(x: f64) -> f64 {
+    libm::expm1(x)
+}
+
+/// Returns `e^x`.
+#[cfg(feature = "libm")]
+pub fn exp(x: f64) -> f64 {
+    libm::exp(x)
+}
+
+/// Returns the natural logarithm of `x`.
+#[cfg(feature = "libm")]
+pub fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
+
+/// Returns `(sin(x), cos(x))`.
+///
+/// The argument is reduced to `[-π/4, π/4]` and then evaluated with Taylor
+/// polynomials, which is accurate to a few ULP for the arguments that occur
+/// in filter design (`|x| <= 2π`).
+#[cfg(not(feature = "libm"))]
+pub fn sin_cos(x: f64) -> (f64, f64) {
+    if !x.is_finite() {
+        return (f64::NAN, f64::NAN);
+    }
+
+    // round to the nearest multiple of π/2
+    let k = x * FRAC_2_PI;
+    let k = if k >= 0.0 { k + 0.5 } else { k - 0.5 } as i64;
+    let r = (x - k as f64 * FRAC_PI_2) - k as f64 * FRAC_PI_2_LO;
+
+    let s = sin_poly(r);
+    let c = cos_poly(r);
+    match k & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+/// Returns the square root of `x`.
+///
+/// Uses Newton's method, starting from an estimate that halves the exponent.
+/// The result is accurate to a few ULP for normal numbers.
+#[cfg(not(feature = "libm"))]
+pub fn sqrt(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 || x == f64::INFINITY {
+        return x;
+    }
+
+    let mut y = f64::from_bits((x.to_bits() >> 1) + (1023_u64 << 51));
+    for _ in 0..8 {
+        y = 0.5 * (y + x / y);
+    }
+    y
+}
+
+/// Returns the four-quadrant arctangent of `y / x` in `[-π, π]`.
+#[cfg(not(feature = "libm"))]
+pub fn atan2(y: f64, x: f64) -> f64 {
+    if x.is_nan() || y.is_nan() {
+        f64::NAN
+    } else if x > 0.0 {
+        atan(y / x)
+    } else if x < 0.0 {
+        if y >= 0.0 {
+            atan(y / x) + PI
+        } else {
+            atan(y / x) - PI
+        }
+    } else if y > 0.0 {
+        FRAC_PI_2
+    } else if y < 0.0 {
+        -FRAC_PI_2
+    } else {
+        0.0
+    }
+}
+
+/// Returns the arccosine of `x` in `[0, π]`, or NaN if `x` is not in
+/// `[-1, 1]`.
+#[cfg(not(feature = "libm"))]
+pub fn acos(x: f64) -> f64 {
+    atan2(sqrt((1.0 - x) * (1.0 + x)), x)
+}
+
+/// Returns the arctangent of `x` in `[-π/2, π/2]`.
+///
+/// Arguments above 1 are mapped to `π/2 - atan(1/x)`. Then, the half-angle
+/// formula `atan(x) = 2 * atan(x / (1 + sqrt(1 + x^2)))` is applied twice,
+/// so that the Taylor series converges quickly for `|x| <= tan(π/16)`.
+#[cfg(not(feature = "libm"))]
+fn atan(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x.abs() > 1.0 {
+        let r = FRAC_PI_2 - atan(1.0 / x.abs());
+        return if x > 0.0 { r } else { -r };
+    }
+
+    let mut x = x;
+    for _ in 0..2 {
+        x /= 1.0 + sqrt(1.0 + x * x);
+    }
+
+    let x2 = x * x;
+    // x - x^3/3 + x^5/5 - ... - x^47/47
+    let mut power = x;
+    let mut sum = x;
+    for n in 1..24 {
+        power *= -x2;
+        sum += power / f64::from(2 * n + 1);
+    }
+    4.0 * sum
+}
+
+/// Returns `e^x - 1`, which is accurate even if `x` is close to zero.
+///
+/// The argument is reduced to `x = n * ln(2) + r` with `|r| <= ln(2)/2`.
+/// Then, `e^r - 1` is evaluated with a Taylor polynomial and scaled by `2^n`.
+#[cfg(not(feature = "libm"))]
+pub fn exp_m1(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x > 709.0 {
+        return f64::INFINITY;
+    }
+    if x < -40.0 {
+        // e^x is below the precision of the result
+        return -1.0;
+    }
+
+    let (n, sum) = exp_m1_reduced(x);
+    if n == 0 {
+        sum
+    } else {
+        // -58 <= n <= 1023, so 2^n is a normal number
+        (sum + 1.0) * pow2(n) - 1.0
+    }
+}
+
+/// Splits `x = n * ln(2) + r` with `|r| <= ln(2)/2` and returns `n` and
+/// `e^r - 1`.
+#[cfg(not(feature = "libm"))]
+fn exp_m1_reduced(x: f64) -> (i64, f64) {
+    // round to the nearest multiple of ln(2)
+    let n = x / LN_2;
+    let n = if n >= 0.0 { n + 0.5 } else { n - 0.5 } as i64;
+    let r = (x - n as f64 * LN_2_HI) - n as f64 * LN_2_LO;
+
+    // r + r^2/2! + ... + r^20/20!
+    let mut term = r;
+    let mut sum = r;
+    for k in 2..=20 {
+        term *= r / f64::from(k);
+        sum += term;
+    }
+    (n, sum)
+}
+
+/// Returns `2^n` for `-1022 <= n <= 1023`.
+#[cfg(not(feature = "libm"))]
+const fn pow2(n: i64) -> f64 {
+    f64::from_bits(((1023 + n) as u64) << 52)
+}
+
+/// Returns `e^x`.
+///
+/// Uses the same range reduction as [`exp_m1`], but also handles results
+/// that are subnormal numbers.
+#[cfg(not(feature = "libm"))]
+pub fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x > 709.0 {
+        return f64::INFINITY;
+    }
+    if x < -746.0 {
+        return 0.0;
+    }
+
+    let (n, sum) = exp_m1_reduced(x);
+    if n < -1022 {
+        // -1077 <= n, so scale in two steps
+        (sum + 1.0) * pow2(n + 1022) * pow2(-1022)
+    } else {
+        (sum + 1.0) * pow2(n)
+    }
+}
+
+/// Returns the natural logarithm of `x`.
+///
+/// `x` is split into `m * 2^e` with `m` in `[√½, √2]`. Then,
+/// `ln(m) = 2 * atanh((m - 1) / (m + 1))` is evaluated with a Taylor
+/// polynomial and `e * ln(2)` is added.
+#[cfg(not(feature = "libm"))]
+pub fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x == f64::INFINITY {
+        return x;
+    }
+
+    // scale subnormal numbers, so that their exponent can be read
+    let (x, offset) = if x < f64::MIN_POSITIVE {
+        (x * f64::from_bits((1023 + 54) << 52), -54)
+    } else {
+        (x, 0)
+    };
+    let bits = x.to_bits();
+    let mut e = ((bits >> 52) & 0x7ff) as i64 - 1023 + offset;
+    let mut m = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | (1023 << 52));
+    if m > SQRT_2 {
+        m /= 2.0;
+        e += 1;
+    }
+
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    // atanh(s) / s = 1 + s^2/3 + s^4/5 + ... + s^22/23
+    let mut sum = 0.0;
+    for k in (1..=23).rev().step_by(2) {
+        sum = sum * s2 + 1.0 / f64::from(k);
+    }
+    let e = e as f64;
+    e * LN_2_HI + (2.0 * s * sum + e * LN_2_LO)
+}
+
+/// Sine for `|x| <= π/4`.
+#[cfg(not(feature = "libm"))]
+fn sin_poly(x: f64) -> f64 {
+    let x2 = x * x;
+    // x - x^3/3! + x^5/5! - ... + x^17/17!
+    let mut term = x;
+    let mut sum = x;
+    for n in (2..=16).step_by(2) {
+        term *= -x2 / (n * (n + 1)) as f64;
+        sum += term;
+    }
+    sum
+}
+
+/// Cosine for `|x| <= π/4`.
+#[cfg(not(feature = "libm"))]
+fn cos_poly(x: f64) -> f64 {
+    let x2 = x * x;
+    // 1 - x^2/2! + x^4/4! - ... + x^18/18!
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in (1..=17).step_by(2) {
+        term *= -x2 / (n * (n + 1)) as f64;
+        sum += term;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sin_cos() {
+        for i in -2000..=2000 {
+            let x = f64::from(i) * 0.005;
+            let (s, c) = sin_cos(x);
+            assert!((s - x.sin()).abs() < 1e-15, "sin({x})");
+            assert!((c - x.cos()).abs() < 1e-15, "cos({x})");
+        }
+        assert!(sin_cos(f64::NAN).0.is_nan());
+    }
+
+    #[test]
+    fn test_sqrt_atan2_acos() {
+        for i in 0..=4000 {
+            let x = f64::from(i) * 0.01;
+            assert!(
+                (sqrt(x) - x.sqrt()).abs() <= 2.0 * f64::EPSILON * x.sqrt(),
+                "sqrt({x})"
+            );
+        }
+        assert!(sqrt(-1.0).is_nan());
+
+        for i in -40..=40 {
+            for j in -40..=40 {
+                let (y, x) = (f64::from(i) * 0.1, f64::from(j) * 0.1);
+                assert!((atan2(y, x) - y.atan2(x)).abs() < 1e-15, "atan2({y}, {x})");
+            }
+        }
+
+        for i in -1000..=1000 {
+            let x = f64::from(i) * 0.001;
+            assert!((acos(x) - x.acos()).abs() < 1e-14, "acos({x})");
+        }
+        assert!(acos(1.5).is_nan());
+    }
+
+    #[test]
+    fn test_exp_m1() {
+        for i in -4000..=4000 {
+            let x = f64::from(i) * 0.01;
+            let expected = x.exp_m1();
+            assert!(
+                (exp_m1(x) - expected).abs() <= 4.0 * f64::EPSILON * expected.abs(),
+                "exp_m1({x})"
+            );
+        }
+        let tiny = -1e-12;
+        assert!((exp_m1(tiny) - tiny.exp_m1()).abs() <= f64::EPSILON * tiny.abs());
+        assert_eq!(exp_m1(-1000.0), -1.0);
+    }
+
+    #[test]
+    fn test_exp_ln() {
+        for i in -7000..=7000 {
+            let x = f64::from(i) * 0.1;
+            let expected = x.exp();
+            assert!(
+                (exp(x) - expected).abs() <= 4.0 * f64::EPSILON * expected,
+                "exp({x})"
+            );
+        }
+        for i in -300..=300 {
+            for m in [1.0, 1.2345, 1.41, 1.42, 1.999_999] {
+                let x = m * 10.0_f64.powi(i);
+                let expected = x.ln();
+                assert!(
+                    (ln(x) - expected).abs() <= 2.0 * f64::EPSILON * expected.abs().max(1.0),
+                    "ln({x})"
+                );
+            }
+        }
+        assert_eq!(ln(1.0), 0.0);
+        assert!((ln(5e-324) - 5e-324_f64.ln()).abs() < 1e-12);
+        assert_eq!(ln(0.0), f64::NEG_INFINITY);
+        assert!(ln(-1.0).is_nan());
+        assert_eq!(exp(-1000.0), 0.0);
+    }
+}
@@ -0,0 +1,202 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`MultiChannelLowpassFilter`].
+
+use crate::{Filter, FilterError, LowpassFilter, Sample};
+
+/// A lowpass filter for audio with multiple channels, such as stereo or 5.1.
+///
+/// Each channel has its own independent [`LowpassFilter`] state, but all
+/// channels share the same parameters. The filter can process interleaved
+/// (`LRLR...`) and planar buffers in-place, so multichannel audio doesn't
+/// need to be de-interleaved or downmixed first.
+///
+/// It is mandatory to operate on values in range `-1.0..=1.0`, which is
+/// also the default in DSP. The number of channels `CH` must be at least
+/// one, which is checked at compile time.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::MultiChannelLowpassFilter;
+///
+/// // interleaved stereo audio: LRLRLR
+/// let mut samples = [0.0, 0.1, 0.5, 0.6, 1.0, 0.9];
+/// let mut filter = MultiChannelLowpassFilter::<f32, 2>::new(44100.0, 120.0);
+/// filter.process_interleaved(&mut samples);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiChannelLowpassFilter<T, const CH: usize> {
+    channels: [LowpassFilter<T>; CH],
+}
+
+impl<T, const CH: usize> MultiChannelLowpassFilter<T, CH> {
+    /// Returns the filters of the individual channels.
+    #[must_use]
+    pub const fn channels(&self) -> &[LowpassFilter<T>; CH] {
+        &self.channels
+    }
+
+    /// Returns the filters of the individual channels, for example to change
+    /// their parameters with [`LowpassFilter::set_cutoff`].
+    #[must_use]
+    pub const fn channels_mut(&mut self) -> &mut [LowpassFilter<T>; CH] {
+        &mut self.channels
+    }
+}
+
+impl<T: Sample, const CH: usize> MultiChannelLowpassFilter<T, CH> {
+    /// Create a new multichannel lowpass filter.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        match Self::try_new(sample_rate_hz, cutoff_frequency_hz) {
+            Ok(filter) => filter,
+            Err(e) => panic!("invalid lowpass filter parameters: {e}"),
+        }
+    }
+
+    /// Create a new multichannel lowpass filter or return an error if
+    /// the parameters are invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`LowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        // Without channels, there are no frames, e.g., the interleaved
+        // audio can't be split into frames.
+        const { assert!(CH > 0, "a multichannel filter needs at least one channel") };
+        let filter = LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)?;
+        Ok(Self {
+            channels: core::array::from_fn(|_| filter.clone()),
+        })
+    }
+
+    /// Filter a single frame, i.e., one sample per channel, and return
+    /// the filtered frame.
+    #[inline]
+    pub fn run(&mut self, frame: [T; CH]) -> [T; CH] {
+        let mut frame = frame;
+        for (sample, filter) in frame.iter_mut().zip(self.channels.iter_mut()) {
+            *sample = filter.run(*sample);
+        }
+        frame
+    }
+
+    /// Filters interleaved audio (`LRLR...` for stereo) in-place.
+    ///
+    /// # Panics
+    /// If the length of the buffer is not a multiple of the number of
+    /// channels.
+    pub fn process_interleaved(&mut self, samples: &mut [T]) {
+        assert_eq!(
+            samples.len() % CH,
+            0,
+            "the length of interleaved audio must be a multiple of the channel count ({CH})"
+        );
+        for frame in samples.chunks_exact_mut(CH) {
+            for (sample, filter) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                *sample = filter.run(*sample);
+            }
+        }
+    }
+
+    /// Filters planar audio, i.e., one buffer per channel, in-place.
+    ///
+    /// The buffers may have different lengths.
+    pub fn process_planar(&mut self, channels: [&mut [T]; CH]) {
+        for (samples, filter) in channels.into_iter().zip(self.channels.iter_mut()) {
+            filter.process_in_place(samples);
+        }
+    }
+
+    /// Reset the internal filter state of all channels.
+    pub fn reset(&mut self) {
+        self.channels.iter_mut().for_each(LowpassFilter::reset);
+    }
+}
+
+impl<T: Sample, const CH: usize> Filter<[T; CH]> for MultiChannelLowpassFilter<T, CH> {
+    #[inline]
+    fn run(&mut self, input: [T; CH]) -> [T; CH] {
+        Self::run(self, input)
+    }
+
+    fn reset(&mut self) {
+        Self::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_interleaved_and_planar_match_mono() {
+        let left = sine_wave_samples(120.0, 44100.0);
+        let right = sine_wave_samples(350.0, 44100.0);
+
+        let mut left_expected = left.clone();
+        let mut right_expected = right.clone();
+        crate::lowpass_filter_f64(&mut left_expected, 44100.0, 90.0);
+        crate::lowpass_filter_f64(&mut right_expected, 44100.0, 90.0);
+
+        let mut interleaved = left
+            .iter()
+            .zip(right.iter())
+            .flat_map(|(l, r)| [*l, *r])
+            .collect::<Vec<_>>();
+        let mut filter = MultiChannelLowpassFilter::<f64, 2>::new(44100.0, 90.0);
+        filter.process_interleaved(&mut interleaved);
+        let (left_actual, right_actual): (Vec<_>, Vec<_>) =
+            interleaved.chunks_exact(2).map(|lr| (lr[0], lr[1])).unzip();
+        assert_eq!(left_actual, left_expected);
+        assert_eq!(right_actual, right_expected);
+
+        let mut left_planar = left;
+        let mut right_planar = right;
+        filter.reset();
+        filter.process_planar([&mut left_planar, &mut right_planar]);
+        assert_eq!(left_planar, left_expected);
+        assert_eq!(right_planar, right_expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interleaved_rejects_incomplete_frames() {
+        let mut filter = MultiChannelLowpassFilter::<f32, 2>::new(44100.0, 90.0);
+        filter.process_interleaved(&mut [0.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,217 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the range policies of [`crate::LowpassFilter`], such as
+//! [`Normalized`] and [`Unbounded`].
+
+use crate::{FilterError, Sample};
+use core::fmt::Debug;
+
+/// Defines which values a filter accepts and emits.
+///
+/// The policy is a type parameter of the filter, so it is chosen at
+/// construction and doesn't cost anything at runtime if it does nothing,
+/// such as [`Unbounded`].
+pub trait RangePolicy<T>: Copy + Debug {
+    /// Checks an input sample. Implementations should only check in debug
+    /// builds, like with `debug_assert!`.
+    fn check_input(&self, input: T);
+
+    /// Restricts an output sample to the range.
+    #[must_use]
+    fn limit(&self, output: T) -> T;
+}
+
+/// Samples can have any value. Neither inputs are checked nor outputs are
+/// clamped.
+///
+/// Use this for data that is not audio, such as ADC counts or temperatures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unbounded;
+
+impl<T> RangePolicy<T> for Unbounded {
+    #[inline]
+    fn check_input(&self, _input: T) {}
+
+    #[inline]
+    fn limit(&self, output: T) -> T {
+        output
+    }
+}
+
+/// Samples must be in range `-1.0..=1.0`, which is the default in DSP.
+///
+/// Inputs are checked in debug builds. Outputs are clamped, as very small
+/// deviations caused by floating point operations are tolerable. This is the
+/// default policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Normalized;
+
+impl<T: Sample> RangePolicy<T> for Normalized {
+    #[inline]
+    fn check_input(&self, input: T) {
+        debug_assert!(
+            (-T::ONE..=T::ONE).contains(&input),
+            "samples must be in range -1.0..=1.0: {input:?}"
+        );
+    }
+
+    #[inline]
+    fn limit(&self, output: T) -> T {
+        output.clamp(-T::ONE, T::ONE)
+    }
+}
+
+/// Samples must be in range `min..=max`.
+///
+/// Like [`Normalized`], inputs are checked in debug builds and outputs are
+/// clamped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "UncheckedRange<T>",
+        bound(deserialize = "T: Sample + serde::Deserialize<'de>")
+    )
+)]
+pub struct CustomRange<T> {
+    min: T,
+    max: T,
+}
+
+/// The serialized form of [`CustomRange`], which is validated when it is
+/// converted.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedRange<T> {
+    min: T,
+    max: T,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Sample> TryFrom<UncheckedRange<T>> for CustomRange<T> {
+    type Error = FilterError;
+
+    fn try_from(range: UncheckedRange<T>) -> Result<Self, Self::Error> {
+        Self::try_new(range.min, range.max)
+    }
+}
+
+impl<T: Sample> CustomRange<T> {
+    /// Creates a new range.
+    ///
+    /// # Panics
+    /// If the range is invalid. See [`Self::try_new`] for the fallible
+    /// variant.
+    #[must_use]
+    pub fn new(min: T, max: T) -> Self {
+        match Self::try_new(min, max) {
+            Ok(range) => range,
+            Err(e) => panic!("invalid range: {e}"),
+        }
+    }
+
+    /// Creates a new range or returns an error if `min` is greater than
+    /// `max` or if one of them is NaN.
+    pub fn try_new(min: T, max: T) -> Result<Self, FilterError> {
+        if min <= max {
+            Ok(Self { min, max })
+        } else {
+            Err(FilterError::InvalidRange)
+        }
+    }
+
+    /// Returns the lower bound.
+    #[must_use]
+    pub const fn min(&self) -> T {
+        self.min
+    }
+
+    /// Returns the upper bound.
+    #[must_use]
+    pub const fn max(&self) -> T {
+        self.max
+    }
+}
+
+impl<T: Sample> RangePolicy<T> for CustomRange<T> {
+    #[inline]
+    fn check_input(&self, input: T) {
+        debug_assert!(
+            (self.min..=self.max).contains(&input),
+            "samples must be in range {:?}..={:?}: {input:?}",
+            self.min,
+            self.max
+        );
+    }
+
+    #[inline]
+    fn limit(&self, output: T) -> T {
+        output.clamp(self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LowpassFilter;
+
+    #[test]
+    fn test_range_policies() {
+        let mut samples = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0];
+        let mut normalized = LowpassFilter::<f64>::new(44100.0, 120.0);
+        let mut unbounded = normalized.clone().with_range_policy(Unbounded);
+        let mut custom = normalized
+            .clone()
+            .with_range_policy(CustomRange::new(-10.0, 2000.0));
+
+        unbounded.process_in_place(&mut samples);
+        let expected = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0].map(|x| custom.run(x));
+        assert_eq!(samples, expected);
+        assert!(samples.iter().any(|x| *x > 1.0));
+
+        // without clamping, both policies are the same as normalized
+        // filtering of scaled samples
+        let scaled = [0.0, 1.0, -5.0, 1551.0, 141.0, 24.0].map(|x| normalized.run(x / 1551.0));
+        for (actual, expected) in samples.iter().zip(scaled) {
+            assert!((actual / 1551.0 - expected).abs() < 1e-12);
+        }
+
+        let mut custom = LowpassFilter::<f32>::new(44100.0, 22050.0)
+            .with_range_policy(CustomRange::new(0.0, 0.5));
+        assert_eq!(custom.run(0.5), 0.5 * custom.alpha);
+        custom.reset_to(0.5);
+        assert_eq!(custom.run(0.5), 0.5);
+        assert_eq!(
+            CustomRange::try_new(1.0, 0.0),
+            Err(FilterError::InvalidRange)
+        );
+        assert_eq!(
+            CustomRange::try_new(f64::NAN, 0.0),
+            Err(FilterError::InvalidRange)
+        );
+    }
+}
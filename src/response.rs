@@ -0,0 +1,154 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the analytical frequency response of [`LowpassFilter`].
+//!
+//! The filter has the transfer function `H(z) = α / (1 - (1 - α) z^-1)`,
+//! which is evaluated on the unit circle at `z = e^(jω)` with
+//! `ω = 2π * frequency / sample_rate`.
+
+use crate::{LowpassFilter, RangePolicy, Sample, math};
+use core::f64::consts::PI;
+
+impl<T: Sample, R: RangePolicy<T>> LowpassFilter<T, R> {
+    /// Returns `(α, cos(ω), sin(ω))` for the given frequency.
+    fn response_terms(&self, frequency_hz: T) -> (f64, f64, f64) {
+        let omega = 2.0 * PI * frequency_hz.to_f64() / self.sample_rate_hz.to_f64();
+        let (sin, cos) = math::sin_cos(omega);
+        (self.alpha_target.to_f64(), cos, sin)
+    }
+
+    /// Returns the magnitude of the frequency response at the given
+    /// frequency, i.e., the factor by which the amplitude of a sine wave is
+    /// scaled. `1.0` means no attenuation.
+    ///
+    /// Like all following analysis functions, this describes the filter
+    /// with its current parameters. A coefficient that is still being
+    /// smoothed is assumed to have reached its target. The response is
+    /// periodic with the sample rate.
+    #[must_use]
+    pub fn magnitude_at(&self, frequency_hz: T) -> T {
+        let (alpha, cos, _) = self.response_terms(frequency_hz);
+        let beta = 1.0 - alpha;
+        T::from_f64(alpha / math::sqrt(1.0 - 2.0 * beta * cos + beta * beta))
+    }
+
+    /// Returns the phase of the frequency response at the given frequency in
+    /// radians. It is negative, as the output lags behind the input.
+    #[must_use]
+    pub fn phase_at(&self, frequency_hz: T) -> T {
+        let (alpha, cos, sin) = self.response_terms(frequency_hz);
+        let beta = 1.0 - alpha;
+        T::from_f64(-math::atan2(beta * sin, 1.0 - beta * cos))
+    }
+
+    /// Returns the group delay at the given frequency in seconds, i.e., how
+    /// much the envelope of a signal around this frequency is delayed.
+    /// Multiply it with the sample rate to get the delay in samples.
+    #[must_use]
+    pub fn group_delay_at(&self, frequency_hz: T) -> T {
+        let (alpha, cos, _) = self.response_terms(frequency_hz);
+        let beta = 1.0 - alpha;
+        let denominator = 1.0 - 2.0 * beta * cos + beta * beta;
+        let samples = beta * (cos - beta) / denominator;
+        T::from_f64(samples / self.sample_rate_hz.to_f64())
+    }
+
+    /// Returns the frequency at which the magnitude of the frequency response
+    /// actually is -3 dB (`1/√2`).
+    ///
+    /// It deviates from the configured cutoff frequency, as the coefficient
+    /// is derived from an analog RC circuit. The deviation grows as the
+    /// cutoff frequency approaches the Nyquist frequency. `None` is returned
+    /// if the attenuation stays below 3 dB up to the Nyquist frequency.
+    #[must_use]
+    pub fn cutoff_frequency_3db_hz(&self) -> Option<T> {
+        let alpha = self.alpha_target.to_f64();
+        let beta = 1.0 - alpha;
+        // solve |H|^2 = 1/2 for cos(ω)
+        let cos = (1.0 + beta * beta - 2.0 * alpha * alpha) / (2.0 * beta);
+        if !(-1.0..=1.0).contains(&cos) {
+            return None;
+        }
+        let omega = math::acos(cos);
+        Some(T::from_f64(
+            omega * self.sample_rate_hz.to_f64() / (2.0 * PI),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+    use core::f64::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn test_response_at_dc() {
+        let filter = LowpassFilter::<f64>::new(44100.0, 1000.0);
+        assert!((filter.magnitude_at(0.0) - 1.0).abs() < 1e-12);
+        assert!(filter.phase_at(0.0).abs() < 1e-12);
+        // β/α samples at DC
+        let alpha = filter.alpha;
+        let expected = (1.0 - alpha) / alpha / 44100.0;
+        assert!((filter.group_delay_at(0.0) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_magnitude_matches_measurement() {
+        let mut filter = LowpassFilter::<f64>::new(44100.0, 500.0);
+        for frequency in [100.0, 500.0, 2000.0] {
+            filter.reset();
+            let mut samples = sine_wave_samples(frequency, 44100.0);
+            filter.process_in_place(&mut samples);
+            // the peak in the second half, after the transient
+            let peak = samples[samples.len() / 2..]
+                .iter()
+                .fold(0.0_f64, |max, x| max.max(x.abs()));
+            let expected = filter.magnitude_at(frequency);
+            assert!(
+                (peak - expected).abs() < 1e-3,
+                "{frequency}: {peak} {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cutoff_frequency_3db() {
+        let filter = LowpassFilter::<f32>::new(44100.0, 1000.0);
+        let cutoff = filter.cutoff_frequency_3db_hz().unwrap();
+        assert!((f64::from(filter.magnitude_at(cutoff)) - FRAC_1_SQRT_2).abs() < 1e-6);
+        // the RC approximation is slightly below the configured cutoff
+        // frequency
+        assert!((900.0..1000.0).contains(&cutoff), "{cutoff}");
+        // the output lags behind, except at the Nyquist frequency, where
+        // the response is real
+        assert!((-PI / 2.0..0.0).contains(&f64::from(filter.phase_at(cutoff))));
+        assert!(filter.phase_at(22050.0).abs() < 1e-6);
+
+        // the deviation is large near the Nyquist frequency
+        let filter = LowpassFilter::<f64>::new(44100.0, 22050.0);
+        let cutoff = filter.cutoff_frequency_3db_hz().unwrap();
+        assert!((12000.0..13000.0).contains(&cutoff), "{cutoff}");
+    }
+}
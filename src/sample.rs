@@ -0,0 +1,690 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the [`Sample`] trait and the conversion of integer PCM
+//! samples (`u8`, `i8`, `i16`, packed 24-bit, and `i32`) to and from `f32`
+//! and `f64`.
+//!
+//! The filters expect normalized floating-point samples in the range
+//! `-1.0..=1.0`, whereas audio files and devices usually provide integers.
+//! [`PcmSample`] converts single samples, and [`filter_pcm_in_place`] and
+//! [`filter_packed_i24_in_place`] filter integer buffers directly.
+//!
+//! # Example
+//! ```rust
+//! use lowpass_filter::LowpassFilter;
+//! use lowpass_filter::sample::{PcmSample, Scaling, filter_pcm_in_place};
+//!
+//! let float: f32 = 16384_i16.to_float(Scaling::Asymmetric);
+//! assert_eq!(float, 0.5);
+//! assert_eq!(i16::from_float_saturating(2.0_f32, Scaling::Symmetric), i16::MAX);
+//! assert_eq!(i16::from_float_checked(2.0_f32, Scaling::Symmetric), None);
+//!
+//! let mut pcm = [0_i16, 8000, 16000, 24000];
+//! let mut filter = LowpassFilter::<f32>::new(44100.0, 120.0);
+//! filter_pcm_in_place(&mut filter, &mut pcm, Scaling::Symmetric);
+//! ```
+
+use crate::Filter;
+use core::fmt::Debug;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A floating-point sample type that the filters of this crate can operate
+/// on.
+///
+/// It is implemented for `f32` and `f64`. With the `num-traits` feature, it is
+/// implemented for all types that implement `num_traits::float::FloatCore`,
+/// `num_traits::ConstZero`, and `num_traits::ConstOne` instead. You can also
+/// implement it for your own types.
+///
+/// Filter coefficients are calculated with `f64` precision and converted with
+/// [`Sample::from_f64`], whereas the samples themselves are processed with
+/// the arithmetic of the type.
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::{LowpassFilter, Sample};
+///
+/// fn smooth<T: Sample>(samples: &mut [T], sample_rate_hz: T) {
+///     let mut filter = LowpassFilter::new(sample_rate_hz, T::from_f64(120.0));
+///     for sample in samples {
+///         *sample = filter.run(*sample);
+///     }
+/// }
+/// ```
+pub trait Sample:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The value `0.0`.
+    const ZERO: Self;
+
+    /// The value `1.0`.
+    const ONE: Self;
+
+    /// Converts an `f64` to the nearest value of this type.
+    fn from_f64(value: f64) -> Self;
+
+    /// Converts the value to an `f64`.
+    fn to_f64(self) -> f64;
+
+    /// Returns `true` if the value is neither infinite nor NaN.
+    #[inline]
+    fn is_finite(self) -> bool {
+        self.to_f64().is_finite()
+    }
+
+    /// Restricts the value to the given range. NaN stays NaN.
+    #[inline]
+    #[must_use]
+    fn clamp(self, min: Self, max: Self) -> Self {
+        if self < min {
+            min
+        } else if self > max {
+            max
+        } else {
+            self
+        }
+    }
+}
+
+#[cfg(not(feature = "num-traits"))]
+macro_rules! impl_sample {
+    ($t:ty) => {
+        impl Sample for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            fn from_f64(value: f64) -> Self {
+                value as Self
+            }
+
+            #[inline]
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+
+            #[inline]
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+
+            #[inline]
+            fn clamp(self, min: Self, max: Self) -> Self {
+                <$t>::clamp(self, min, max)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "num-traits"))]
+impl_sample!(f32);
+#[cfg(not(feature = "num-traits"))]
+impl_sample!(f64);
+
+#[cfg(feature = "num-traits")]
+impl<T> Sample for T
+where
+    T: num_traits::float::FloatCore + num_traits::ConstZero + num_traits::ConstOne + Debug,
+{
+    const ZERO: Self = <Self as num_traits::ConstZero>::ZERO;
+    const ONE: Self = <Self as num_traits::ConstOne>::ONE;
+
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        <Self as num_traits::NumCast>::from(value).unwrap_or_else(Self::nan)
+    }
+
+    #[inline]
+    fn to_f64(self) -> f64 {
+        num_traits::ToPrimitive::to_f64(&self).unwrap_or(f64::NAN)
+    }
+
+    #[inline]
+    fn is_finite(self) -> bool {
+        num_traits::float::FloatCore::is_finite(self)
+    }
+}
+
+/// How integer PCM samples are mapped to the range `-1.0..=1.0`.
+///
+/// Both conventions are common, so the one of the audio source or sink
+/// should be used. The difference is less than one LSB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scaling {
+    /// Divides by the largest value, e.g., `32767` for `i16`. `1.0` and
+    /// `-1.0` map to `MAX` and `-MAX`, so the scaling is the same in both
+    /// directions. The smallest value `MIN` is clamped to `-1.0`.
+    #[default]
+    Symmetric,
+    /// Divides by `2^(bits - 1)`, e.g., `32768` for `i16`. `MIN` maps to
+    /// exactly `-1.0` and `MAX` to slightly less than `1.0`, so `1.0` is
+    /// out of range.
+    Asymmetric,
+}
+
+/// An integer PCM sample that can be converted to and from normalized
+/// floating-point samples.
+///
+/// It is implemented for `u8` (unsigned with an offset of `128`, as in 8-bit
+/// WAV files), `i8`, `i16`, [`I24`], and `i32`. Converting from `i32` to
+/// `f32` loses precision, as `f32` only has a 24-bit mantissa.
+///
+/// Float values are rounded to the nearest integer, with ties rounded away
+/// from zero.
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait PcmSample: Copy + Debug + private::Sealed {
+    /// The number of bits of the sample.
+    const BITS: u32;
+
+    /// Converts the sample to a floating-point sample in the range
+    /// `-1.0..=1.0`.
+    #[must_use]
+    fn to_float<F: Sample>(self, scaling: Scaling) -> F {
+        let value = self.to_centered() as f64;
+        let value = match scaling {
+            Scaling::Symmetric => (value / max_value(Self::BITS)).max(-1.0),
+            Scaling::Asymmetric => value / full_scale(Self::BITS),
+        };
+        F::from_f64(value)
+    }
+
+    /// Converts a floating-point sample to an integer sample. Returns `None`
+    /// if the value is NaN, infinite, or outside of the range of the integer
+    /// type after scaling.
+    #[must_use]
+    fn from_float_checked<F: Sample>(value: F, scaling: Scaling) -> Option<Self> {
+        let value = scale(value.to_f64(), scaling, Self::BITS);
+        if !value.is_finite() {
+            return None;
+        }
+        let value = round(value);
+        let max = max_value(Self::BITS) as i64;
+        (-max - 1..=max)
+            .contains(&value)
+            .then(|| Self::from_centered(value))
+    }
+
+    /// Converts a floating-point sample to an integer sample. Values outside
+    /// of the range of the integer type, including infinity, are clamped to
+    /// `MIN` or `MAX`. NaN becomes silence.
+    #[must_use]
+    fn from_float_saturating<F: Sample>(value: F, scaling: Scaling) -> Self {
+        let value = scale(value.to_f64(), scaling, Self::BITS);
+        if value.is_nan() {
+            return Self::from_centered(0);
+        }
+        let max = max_value(Self::BITS) as i64;
+        Self::from_centered(round(value).clamp(-max - 1, max))
+    }
+}
+
+mod private {
+    pub trait Sealed: Sized {
+        /// Returns the signed value, i.e., without the offset of unsigned
+        /// types.
+        fn to_centered(self) -> i64;
+
+        /// Creates the sample from a signed value in the range of the type.
+        fn from_centered(value: i64) -> Self;
+    }
+}
+
+/// Returns the largest value of a signed integer with the given bits.
+const fn max_value(bits: u32) -> f64 {
+    full_scale(bits) - 1.0
+}
+
+/// Returns `2^(bits - 1)`.
+const fn full_scale(bits: u32) -> f64 {
+    (1_i64 << (bits - 1)) as f64
+}
+
+/// Scales a normalized value to the range of the integer type.
+fn scale(value: f64, scaling: Scaling, bits: u32) -> f64 {
+    match scaling {
+        Scaling::Symmetric => value * max_value(bits),
+        Scaling::Asymmetric => value * full_scale(bits),
+    }
+}
+
+/// Rounds to the nearest integer, with ties away from zero. The cast
+/// saturates for values outside of the range of `i64`.
+#[allow(clippy::cast_possible_truncation)]
+fn round(value: f64) -> i64 {
+    let truncated = value as i64;
+    let fraction = value - truncated as f64;
+    if fraction >= 0.5 {
+        truncated.saturating_add(1)
+    } else if fraction <= -0.5 {
+        truncated.saturating_sub(1)
+    } else {
+        truncated
+    }
+}
+
+macro_rules! impl_pcm_sample {
+    ($t:ty, $bits:literal, $offset:literal) => {
+        impl PcmSample for $t {
+            const BITS: u32 = $bits;
+        }
+
+        impl private::Sealed for $t {
+            #[inline]
+            fn to_centered(self) -> i64 {
+                i64::from(self) - $offset
+            }
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            fn from_centered(value: i64) -> Self {
+                (value + $offset) as Self
+            }
+        }
+    };
+}
+
+impl_pcm_sample!(u8, 8, 128);
+impl_pcm_sample!(i8, 8, 0);
+impl_pcm_sample!(i16, 16, 0);
+impl_pcm_sample!(i32, 32, 0);
+
+impl PcmSample for I24 {
+    const BITS: u32 = 24;
+}
+
+impl private::Sealed for I24 {
+    #[inline]
+    fn to_centered(self) -> i64 {
+        i64::from(self.to_i32())
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_centered(value: i64) -> Self {
+        Self::from_i32_wrapping(value as i32)
+    }
+}
+
+/// A signed 24-bit sample, stored as three little-endian bytes as in packed
+/// 24-bit PCM, e.g., in WAV files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct I24([u8; 3]);
+
+impl I24 {
+    /// The smallest value, `-2^23`.
+    pub const MIN: Self = Self::from_i32_wrapping(-(1 << 23));
+
+    /// The largest value, `2^23 - 1`.
+    pub const MAX: Self = Self::from_i32_wrapping((1 << 23) - 1);
+
+    /// Creates a sample from an `i32` or returns `None` if the value is
+    /// outside of `-2^23..2^23`.
+    #[must_use]
+    pub const fn new(value: i32) -> Option<Self> {
+        if value >= -(1 << 23) && value < (1 << 23) {
+            Some(Self::from_i32_wrapping(value))
+        } else {
+            None
+        }
+    }
+
+    /// Keeps the lower 24 bits of the value.
+    const fn from_i32_wrapping(value: i32) -> Self {
+        let [b0, b1, b2, _] = value.to_le_bytes();
+        Self([b0, b1, b2])
+    }
+
+    /// Creates a sample from its little-endian representation.
+    #[must_use]
+    pub const fn from_le_bytes(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the little-endian representation of the sample.
+    #[must_use]
+    pub const fn to_le_bytes(self) -> [u8; 3] {
+        self.0
+    }
+
+    /// Returns the value as `i32`.
+    #[must_use]
+    pub const fn to_i32(self) -> i32 {
+        let [b0, b1, b2] = self.0;
+        // shift the sign bit to the top and back to sign-extend
+        i32::from_le_bytes([0, b0, b1, b2]) >> 8
+    }
+}
+
+impl From<I24> for i32 {
+    fn from(value: I24) -> Self {
+        value.to_i32()
+    }
+}
+
+/// Transforms two stereo samples (that reflect the same point in time on
+/// different channels) into one mono sample. The average is rounded towards
+/// zero.
+#[inline]
+#[must_use]
+pub fn stereo_to_mono<P: PcmSample>(left: P, right: P) -> P {
+    P::from_centered((left.to_centered() + right.to_centered()) / 2)
+}
+
+/// Converts integer PCM samples to floating-point samples. See
+/// [`PcmSample::to_float`].
+///
+/// # Panics
+/// If `input` and `output` have different lengths.
+pub fn pcm_to_float<P: PcmSample, F: Sample>(input: &[P], output: &mut [F], scaling: Scaling) {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "input and output must have the same length"
+    );
+    for (input, output) in input.iter().zip(output) {
+        *output = input.to_float(scaling);
+    }
+}
+
+/// Converts floating-point samples to integer PCM samples, clamping values
+/// that are out of range. See [`PcmSample::from_float_saturating`].
+///
+/// # Panics
+/// If `input` and `output` have different lengths.
+pub fn float_to_pcm_saturating<F: Sample, P: PcmSample>(
+    input: &[F],
+    output: &mut [P],
+    scaling: Scaling,
+) {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "input and output must have the same length"
+    );
+    for (input, output) in input.iter().zip(output) {
+        *output = P::from_float_saturating(*input, scaling);
+    }
+}
+
+/// Filters integer PCM samples in-place with any [`Filter`].
+///
+/// Each sample is converted to a float, filtered, and converted back. The
+/// output saturates, e.g., if the filter overshoots. The state of the filter
+/// is kept, so consecutive buffers can be filtered.
+///
+/// # Arguments
+/// - `filter`: Any filter of this crate, e.g., a [`LowpassFilter`](crate::LowpassFilter).
+/// - `samples`: The integer samples.
+/// - `scaling`: The mapping between integers and floats.
+pub fn filter_pcm_in_place<P: PcmSample, F: Sample, L: Filter<F> + ?Sized>(
+    filter: &mut L,
+    samples: &mut [P],
+    scaling: Scaling,
+) {
+    for sample in samples {
+        let output = filter.run(sample.to_float(scaling));
+        *sample = P::from_float_saturating(output, scaling);
+    }
+}
+
+/// Filters packed little-endian 24-bit PCM samples in-place, i.e., three
+/// bytes per sample. See [`filter_pcm_in_place`].
+///
+/// # Panics
+/// If the length of `bytes` is not a multiple of three.
+pub fn filter_packed_i24_in_place<F: Sample, L: Filter<F> + ?Sized>(
+    filter: &mut L,
+    bytes: &mut [u8],
+    scaling: Scaling,
+) {
+    assert_eq!(
+        bytes.len() % 3,
+        0,
+        "packed 24-bit samples must consist of three bytes each"
+    );
+    for chunk in bytes.chunks_exact_mut(3) {
+        let sample = I24::from_le_bytes([chunk[0], chunk[1], chunk[2]]);
+        let output = filter.run(sample.to_float(scaling));
+        chunk.copy_from_slice(&I24::from_float_saturating(output, scaling).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LowpassFilter, lowpass_filter};
+    use core::ops::{Add, Div, Mul, Neg, Sub};
+
+    /// A user-defined sample type.
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    struct Wrapper(f64);
+
+    impl Add for Wrapper {
+        type Output = Self;
+        fn add(self, rhs: Self) -> Self {
+            Self(self.0 + rhs.0)
+        }
+    }
+
+    impl Sub for Wrapper {
+        type Output = Self;
+        fn sub(self, rhs: Self) -> Self {
+            Self(self.0 - rhs.0)
+        }
+    }
+
+    impl Mul for Wrapper {
+        type Output = Self;
+        fn mul(self, rhs: Self) -> Self {
+            Self(self.0 * rhs.0)
+        }
+    }
+
+    impl Div for Wrapper {
+        type Output = Self;
+        fn div(self, rhs: Self) -> Self {
+            Self(self.0 / rhs.0)
+        }
+    }
+
+    impl Neg for Wrapper {
+        type Output = Self;
+        fn neg(self) -> Self {
+            Self(-self.0)
+        }
+    }
+
+    impl Sample for Wrapper {
+        const ZERO: Self = Self(0.0);
+        const ONE: Self = Self(1.0);
+
+        fn from_f64(value: f64) -> Self {
+            Self(value)
+        }
+
+        fn to_f64(self) -> f64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_user_defined_sample_type() {
+        let mut expected = [0.0, 0.5, 1.0, -0.25, -1.0];
+        let mut actual = expected.map(Wrapper);
+        lowpass_filter(&mut expected, 44100.0, 120.0);
+        lowpass_filter(&mut actual, Wrapper(44100.0), Wrapper(120.0));
+        assert_eq!(actual, expected.map(Wrapper));
+
+        assert!(LowpassFilter::try_new(Wrapper(44100.0), Wrapper(f64::NAN)).is_err());
+        assert_eq!(
+            Wrapper(2.0).clamp(-Wrapper::ONE, Wrapper::ONE),
+            Wrapper(1.0)
+        );
+    }
+
+    #[test]
+    fn test_pcm_round_trip() {
+        for value in i16::MIN + 1..=i16::MAX {
+            let float: f32 = value.to_float(Scaling::Symmetric);
+            assert_eq!(
+                i16::from_float_checked(float, Scaling::Symmetric),
+                Some(value)
+            );
+            let float: f64 = value.to_float(Scaling::Asymmetric);
+            assert_eq!(
+                i16::from_float_checked(float, Scaling::Asymmetric),
+                Some(value)
+            );
+        }
+        for value in u8::MIN..=u8::MAX {
+            let float: f32 = value.to_float(Scaling::Asymmetric);
+            assert_eq!(
+                u8::from_float_checked(float, Scaling::Asymmetric),
+                Some(value)
+            );
+        }
+        for value in [-(1 << 23), -1, 0, 1, (1 << 23) - 1] {
+            let sample = I24::new(value).unwrap();
+            let float: f64 = sample.to_float(Scaling::Symmetric);
+            assert_eq!(
+                I24::from_float_checked(float, Scaling::Symmetric).map(i32::from),
+                Some(value.max(-(1 << 23) + 1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_pcm_scaling() {
+        assert_eq!(i16::MIN.to_float::<f32>(Scaling::Symmetric), -1.0);
+        assert_eq!(i16::MIN.to_float::<f32>(Scaling::Asymmetric), -1.0);
+        assert_eq!(i16::MAX.to_float::<f32>(Scaling::Symmetric), 1.0);
+        assert_eq!(i32::MAX.to_float::<f64>(Scaling::Symmetric), 1.0);
+        assert_eq!(i32::MIN.to_float::<f64>(Scaling::Asymmetric), -1.0);
+        assert_eq!(128_u8.to_float::<f32>(Scaling::Symmetric), 0.0);
+        assert_eq!(0_u8.to_float::<f32>(Scaling::Asymmetric), -1.0);
+        assert_eq!(i8::MAX.to_float::<f32>(Scaling::Asymmetric), 127.0 / 128.0);
+        assert_eq!(I24::MIN.to_float::<f64>(Scaling::Asymmetric), -1.0);
+
+        assert_eq!(i16::from_float_checked(1.0_f32, Scaling::Asymmetric), None);
+        assert_eq!(
+            i16::from_float_checked(-1.0_f32, Scaling::Symmetric),
+            Some(-i16::MAX)
+        );
+        assert_eq!(i16::from_float_checked(f32::NAN, Scaling::Symmetric), None);
+        assert_eq!(
+            i16::from_float_checked(1.5 / 32767.0_f64, Scaling::Symmetric),
+            Some(2)
+        );
+        assert_eq!(
+            i16::from_float_checked(-1.5 / 32767.0_f64, Scaling::Symmetric),
+            Some(-2)
+        );
+        assert_eq!(
+            i16::from_float_checked(1.4 / 32767.0_f64, Scaling::Symmetric),
+            Some(1)
+        );
+
+        assert_eq!(
+            i16::from_float_saturating(1.0_f32, Scaling::Asymmetric),
+            i16::MAX
+        );
+        assert_eq!(
+            i32::from_float_saturating(f64::INFINITY, Scaling::Symmetric),
+            i32::MAX
+        );
+        assert_eq!(u8::from_float_saturating(-2.0_f32, Scaling::Symmetric), 0);
+        assert_eq!(u8::from_float_saturating(f32::NAN, Scaling::Symmetric), 128);
+        assert_eq!(
+            I24::from_float_saturating(f64::NEG_INFINITY, Scaling::Symmetric),
+            I24::MIN
+        );
+    }
+
+    #[test]
+    fn test_i24() {
+        assert_eq!(I24::MIN.to_i32(), -8_388_608);
+        assert_eq!(I24::MAX.to_i32(), 8_388_607);
+        assert_eq!(I24::new(8_388_608), None);
+        assert_eq!(I24::new(-8_388_609), None);
+        assert_eq!(I24::new(-2).unwrap().to_le_bytes(), [0xfe, 0xff, 0xff]);
+        assert_eq!(I24::from_le_bytes([0x56, 0x34, 0x12]).to_i32(), 0x12_3456);
+        assert_eq!(I24::from_le_bytes([0x00, 0x00, 0x80]), I24::MIN);
+    }
+
+    #[test]
+    fn test_stereo_to_mono() {
+        assert_eq!(stereo_to_mono(i16::MAX, i16::MAX), i16::MAX);
+        assert_eq!(stereo_to_mono(i16::MIN, i16::MAX), 0);
+        assert_eq!(stereo_to_mono(-3_i16, 0), -1);
+        assert_eq!(stereo_to_mono(0_u8, 255), 128);
+        assert_eq!(stereo_to_mono(I24::MIN, I24::MIN), I24::MIN);
+    }
+
+    #[test]
+    fn test_filter_pcm_in_place() {
+        let input = [0_i16, 10000, 20000, 30000, -32768, 5, 32767, -100];
+
+        let mut expected = [0.0_f32; 8];
+        pcm_to_float(&input, &mut expected, Scaling::Asymmetric);
+        lowpass_filter(&mut expected, 44100.0, 5000.0);
+        let mut expected_pcm = [0_i16; 8];
+        float_to_pcm_saturating(&expected, &mut expected_pcm, Scaling::Asymmetric);
+
+        let mut actual = input;
+        let mut filter = LowpassFilter::new(44100.0, 5000.0);
+        filter_pcm_in_place(&mut filter, &mut actual, Scaling::Asymmetric);
+        assert_eq!(actual, expected_pcm);
+
+        let mut expected_i24 = input.map(|x| I24::new(i32::from(x) << 8).unwrap());
+        let mut bytes = expected_i24
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<std::vec::Vec<_>>();
+        filter_pcm_in_place(
+            &mut LowpassFilter::<f64>::new(44100.0, 5000.0),
+            &mut expected_i24,
+            Scaling::Symmetric,
+        );
+        filter_packed_i24_in_place(
+            &mut LowpassFilter::<f64>::new(44100.0, 5000.0),
+            &mut bytes,
+            Scaling::Symmetric,
+        );
+        let actual_i24 = bytes
+            .chunks_exact(3)
+            .map(|x| I24::from_le_bytes([x[0], x[1], x[2]]))
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(actual_i24, expected_i24);
+    }
+}
@@ -0,0 +1,234 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for the `serde` support of [`LowpassFilter`].
+//!
+//! The filter is serialized as a versioned checkpoint that contains the
+//! configuration, the derived coefficient, and the live state. When it is
+//! deserialized, all values are validated, so that a corrupted checkpoint
+//! can't produce an invalid filter.
+//!
+//! The values are restored exactly, if the format does so. For example,
+//! enable the `float_roundtrip` feature of `serde_json`.
+
+use crate::{FilterError, InitialState, LowpassFilter, RangePolicy, Sample};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The version of the checkpoint format. It is increased whenever the format
+/// changes in an incompatible way.
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// The serialized form of a [`LowpassFilter`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename = "LowpassFilter")]
+struct Checkpoint<T, R> {
+    version: u32,
+    sample_rate_hz: T,
+    cutoff_frequency_hz: T,
+    /// The coefficient that belongs to the parameters. It is checked against
+    /// the configuration and restored exactly, as filters that were created
+    /// from a coefficient, e.g., with [`LowpassFilter::from_alpha`], don't
+    /// recalculate it from the cutoff frequency.
+    alpha_target: T,
+    /// The coefficient that is currently in use. It differs from
+    /// `alpha_target` while the coefficient is being smoothed.
+    alpha: T,
+    smoothing_time_s: T,
+    prev: T,
+    next_is_first: bool,
+    initial_state: InitialState<T>,
+    range: R,
+}
+
+impl<T: Sample, R: RangePolicy<T>> From<&LowpassFilter<T, R>> for Checkpoint<T, R> {
+    fn from(filter: &LowpassFilter<T, R>) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            sample_rate_hz: filter.sample_rate_hz,
+            cutoff_frequency_hz: filter.cutoff_frequency_hz,
+            alpha_target: filter.alpha_target,
+            alpha: filter.alpha,
+            smoothing_time_s: filter.smoothing_time_s,
+            prev: filter.prev,
+            next_is_first: filter.next_is_first,
+            initial_state: filter.initial_state,
+            range: filter.range,
+        }
+    }
+}
+
+impl<T: Sample, R: RangePolicy<T>> TryFrom<Checkpoint<T, R>> for LowpassFilter<T, R> {
+    type Error = FilterError;
+
+    fn try_from(checkpoint: Checkpoint<T, R>) -> Result<Self, Self::Error> {
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(FilterError::UnsupportedVersion);
+        }
+
+        // Validates the configuration. The cutoff frequency of a filter that
+        // was created from a coefficient may be above the Nyquist frequency.
+        let sample_rate_hz = checkpoint.sample_rate_hz;
+        let cutoff_frequency_hz = checkpoint.cutoff_frequency_hz;
+        FilterError::check_sample_rate(sample_rate_hz.to_f64())?;
+        FilterError::check_cutoff_frequency(cutoff_frequency_hz.to_f64())?;
+
+        // the coefficient must belong to the configuration
+        let expected_alpha_target =
+            LowpassFilter::<T>::calc_alpha(sample_rate_hz, cutoff_frequency_hz).to_f64();
+        let alpha_target = checkpoint.alpha_target.to_f64();
+        if (alpha_target - expected_alpha_target).abs() > expected_alpha_target * 1e-6 {
+            return Err(FilterError::InvalidState);
+        }
+
+        let alpha = checkpoint.alpha.to_f64();
+        let initial_value_is_finite = match checkpoint.initial_state {
+            InitialState::Value(value) => value.is_finite(),
+            InitialState::Zero | InitialState::FirstSample => true,
+        };
+        let is_valid = alpha_target > 0.0
+            && alpha_target <= 1.0
+            && alpha > 0.0
+            && alpha <= 1.0
+            && checkpoint.prev.is_finite()
+            && initial_value_is_finite;
+        if !is_valid {
+            return Err(FilterError::InvalidState);
+        }
+
+        let mut filter = LowpassFilter::from_validated(
+            sample_rate_hz,
+            cutoff_frequency_hz,
+            checkpoint.alpha_target,
+        )
+        .with_range_policy(checkpoint.range);
+        filter.try_set_coefficient_smoothing(checkpoint.smoothing_time_s)?;
+        filter.alpha = checkpoint.alpha;
+        filter.prev = checkpoint.prev;
+        filter.next_is_first = checkpoint.next_is_first;
+        filter.initial_state = checkpoint.initial_state;
+        Ok(filter)
+    }
+}
+
+impl<T, R> Serialize for LowpassFilter<T, R>
+where
+    T: Sample + Serialize,
+    R: RangePolicy<T> + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Checkpoint::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, T, R> Deserialize<'de> for LowpassFilter<T, R>
+where
+    T: Sample + Deserialize<'de>,
+    R: RangePolicy<T> + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let checkpoint = Checkpoint::deserialize(deserializer)?;
+        Self::try_from(checkpoint).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomRange, Unbounded};
+    use std::string::String;
+
+    #[test]
+    fn test_serde_roundtrip_keeps_state() {
+        let mut filter = LowpassFilter::<f64>::new(44100.0, 120.0)
+            .with_initial_state(InitialState::FirstSample)
+            .with_range_policy(CustomRange::new(-2.0, 2.0));
+        filter.set_coefficient_smoothing(0.01);
+        for _ in 0..100 {
+            filter.run(1.5);
+        }
+        filter.set_cutoff(500.0);
+        filter.run(1.5);
+
+        let json = serde_json::to_string(&filter).unwrap();
+        let mut restored: LowpassFilter<f64, CustomRange<f64>> =
+            serde_json::from_str(&json).unwrap();
+        for _ in 0..1000 {
+            assert_eq!(restored.run(-1.5), filter.run(-1.5));
+        }
+        restored.reset();
+        assert_eq!(restored.run(0.75), 0.75);
+    }
+
+    #[test]
+    fn test_serde_roundtrip_keeps_exact_alpha() {
+        // the alpha that is recalculated from the cutoff frequency differs
+        // in the last bits
+        for (sample_rate_hz, period) in [(100.0, 9.0), (1.0, 20.0), (44100.0, 1.7)] {
+            let mut filter = LowpassFilter::<f64>::from_ema_period(sample_rate_hz, period);
+            filter.run(0.3);
+
+            let json = serde_json::to_string(&filter).unwrap();
+            let mut restored: LowpassFilter<f64> = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored.alpha().to_bits(), filter.alpha().to_bits());
+            for i in 0..1000 {
+                let input = (f64::from(i) * 0.1).sin() * 0.9;
+                assert_eq!(restored.run(input).to_bits(), filter.run(input).to_bits());
+            }
+        }
+
+        // the equivalent cutoff frequency is above the Nyquist frequency
+        let filter = LowpassFilter::<f64>::from_alpha(44100.0, 0.9);
+        let json = serde_json::to_string(&filter).unwrap();
+        let restored: LowpassFilter<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.alpha(), 0.9);
+    }
+
+    #[test]
+    fn test_serde_rejects_corrupted_checkpoints() {
+        let filter = LowpassFilter::<f32>::new(44100.0, 120.0).with_range_policy(Unbounded);
+        let json = serde_json::to_string(&filter).unwrap();
+        let deserialize =
+            |json: String| serde_json::from_str::<LowpassFilter<f32, Unbounded>>(&json);
+        assert!(deserialize(json.clone()).is_ok());
+
+        let corruptions = [
+            ("\"version\":1", "\"version\":2"),
+            (
+                "\"cutoff_frequency_hz\":120.0",
+                "\"cutoff_frequency_hz\":30000.0",
+            ),
+            ("\"alpha\":", "\"alpha\":-"),
+            ("\"alpha_target\":", "\"alpha_target\":1"),
+            ("\"smoothing_time_s\":0.0", "\"smoothing_time_s\":-1.0"),
+            ("\"prev\":0.0", "\"prev\":1e39"),
+        ];
+        for (from, to) in corruptions {
+            assert!(json.contains(from), "{json}");
+            assert!(deserialize(json.replace(from, to)).is_err(), "{from}");
+        }
+
+        let json = json.replace("\"range\":null", "\"range\":{\"min\":1.0,\"max\":0.0}");
+        assert!(serde_json::from_str::<LowpassFilter<f32, CustomRange<f32>>>(&json).is_err());
+    }
+}
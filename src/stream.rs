@@ -0,0 +1,208 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for [`LowpassStream`].
+
+use crate::{FilterError, LowpassFilter, Normalized, RangePolicy, Sample};
+
+/// A streaming lowpass processor for audio callbacks.
+///
+/// It owns the filter state across callbacks, so a signal can be filtered
+/// in blocks of any length, including empty blocks. The output is the same
+/// as filtering the whole signal at once with [`LowpassFilter`]. This avoids
+/// the discontinuities at block boundaries that occur when a new filter is
+/// created for each block, e.g., with [`crate::lowpass_filter`].
+///
+/// If the sample rate of the audio device changes between callbacks, the
+/// coefficient is recalculated while the state is kept. See
+/// [`Self::process_with_sample_rate`].
+///
+/// # Example
+/// ```rust
+/// use lowpass_filter::LowpassStream;
+///
+/// let mut stream = LowpassStream::<f32>::new(44100.0, 120.0);
+///
+/// // called by the audio backend with blocks of varying length
+/// let mut callback = |block: &mut [f32], sample_rate_hz: f32| {
+///     stream.process_with_sample_rate(block, sample_rate_hz).unwrap();
+/// };
+///
+/// callback(&mut [0.1, 0.2, 0.3], 44100.0);
+/// callback(&mut [0.4], 44100.0);
+/// callback(&mut [0.5, 0.6], 48000.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LowpassStream<T, R = Normalized> {
+    filter: LowpassFilter<T, R>,
+}
+
+impl<T, R> LowpassStream<T, R> {
+    /// Creates a stream from an existing filter and its state, for example
+    /// one with a different [`RangePolicy`].
+    #[must_use]
+    pub const fn from_filter(filter: LowpassFilter<T, R>) -> Self {
+        Self { filter }
+    }
+
+    /// Returns the underlying filter.
+    #[must_use]
+    pub const fn filter(&self) -> &LowpassFilter<T, R> {
+        &self.filter
+    }
+
+    /// Returns the underlying filter, for example to change the cutoff
+    /// frequency with [`LowpassFilter::set_cutoff`].
+    #[must_use]
+    pub const fn filter_mut(&mut self) -> &mut LowpassFilter<T, R> {
+        &mut self.filter
+    }
+
+    /// Returns the underlying filter and its state.
+    #[must_use]
+    pub fn into_inner(self) -> LowpassFilter<T, R> {
+        self.filter
+    }
+}
+
+impl<T: Sample> LowpassStream<T> {
+    /// Create a new streaming lowpass processor.
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    ///
+    /// # Panics
+    /// If the parameters are invalid. See [`Self::try_new`] for the
+    /// fallible variant.
+    #[must_use]
+    pub fn new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Self {
+        Self::from_filter(LowpassFilter::new(sample_rate_hz, cutoff_frequency_hz))
+    }
+
+    /// Create a new streaming lowpass processor or return an error if the
+    /// parameters are invalid.
+    ///
+    /// The parameters are validated the same way as in
+    /// [`LowpassFilter::try_new`].
+    ///
+    /// # Arguments
+    /// - `sample_rate_hz`: Sample rate in Hz (e.g., 48000.0).
+    /// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+    pub fn try_new(sample_rate_hz: T, cutoff_frequency_hz: T) -> Result<Self, FilterError> {
+        LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz).map(Self::from_filter)
+    }
+}
+
+impl<T: Sample, R: RangePolicy<T>> LowpassStream<T, R> {
+    /// Filters the next block of the stream in-place.
+    #[inline]
+    pub fn process(&mut self, block: &mut [T]) {
+        self.filter.process_in_place(block);
+    }
+
+    /// Filters the next block of the stream from `input` to `output`.
+    ///
+    /// # Panics
+    /// If `input` and `output` have different lengths.
+    #[inline]
+    pub fn process_into(&mut self, input: &[T], output: &mut [T]) {
+        self.filter.process_into(input, output);
+    }
+
+    /// Filters the next block of the stream in-place, which was recorded
+    /// with the given sample rate.
+    ///
+    /// If the sample rate differs from the previous one, the coefficient is
+    /// recalculated and the state is kept. If the new sample rate is invalid,
+    /// an error is returned and neither the block nor the stream is changed.
+    pub fn process_with_sample_rate(
+        &mut self,
+        block: &mut [T],
+        sample_rate_hz: T,
+    ) -> Result<(), FilterError> {
+        if sample_rate_hz != self.filter.sample_rate_hz() {
+            self.filter.try_set_sample_rate(sample_rate_hz)?;
+        }
+        self.process(block);
+        Ok(())
+    }
+
+    /// Reset the filter state, e.g., when the stream is interrupted.
+    pub const fn reset(&mut self) {
+        self.filter.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine_wave_samples;
+
+    #[test]
+    fn test_stream_matches_whole_signal() {
+        let samples = sine_wave_samples(120.0, 44100.0);
+        let mut expected = samples.clone();
+        crate::lowpass_filter_f64(&mut expected, 44100.0, 90.0);
+
+        let mut stream = LowpassStream::<f64>::new(44100.0, 90.0);
+        let mut actual = samples;
+        let mut rest = actual.as_mut_slice();
+        for len in [0, 1, 7, 512, 0, 3, 4096].into_iter().cycle() {
+            if rest.is_empty() {
+                break;
+            }
+            let (block, tail) = rest.split_at_mut(len.min(rest.len()));
+            stream.process_with_sample_rate(block, 44100.0).unwrap();
+            rest = tail;
+        }
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_stream_sample_rate_change() {
+        let mut stream = LowpassStream::<f32>::new(44100.0, 120.0);
+        let mut block = [0.5; 64];
+        stream
+            .process_with_sample_rate(&mut block, 44100.0)
+            .unwrap();
+        let mut expected = stream.filter().clone();
+        expected.set_sample_rate(48000.0);
+
+        let mut block = [0.5; 64];
+        stream
+            .process_with_sample_rate(&mut block, 48000.0)
+            .unwrap();
+        assert_eq!(stream.filter().sample_rate_hz(), 48000.0);
+        assert_eq!(block, [0.5; 64].map(|x| expected.run(x)));
+
+        // the cutoff frequency is above the Nyquist frequency of 200 Hz
+        let mut block = [0.5; 64];
+        assert_eq!(
+            stream.process_with_sample_rate(&mut block, 200.0),
+            Err(FilterError::NyquistViolation)
+        );
+        assert_eq!(block, [0.5; 64]);
+        assert_eq!(stream.filter().sample_rate_hz(), 48000.0);
+    }
+}
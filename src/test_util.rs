@@ -24,99 +24,16 @@ SOFTWARE.
 
 #![allow(unused)]
 
-use hound::{SampleFormat, WavSpec};
-use itertools::Itertools;
+// The examples include this file, too, where the crate root is the example.
+#[cfg(test)]
+use crate::analysis::energy;
+#[cfg(test)]
+use crate::{Filter, apply_filter};
 use std::f64::consts::PI;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::vec::Vec;
 
-/// Reads a WAV file to mono audio. Returns the samples as mono audio.
-/// Additionally, it returns the sampling rate of the file.
-pub fn read_wav_to_mono<T: AsRef<Path>>(file: T) -> (Vec<i16>, WavSpec) {
-    let mut reader = hound::WavReader::open(file).unwrap();
-    let header = reader.spec();
-
-    // owning vector with original data in i16 format
-    let data = reader
-        .samples::<i16>()
-        .map(|s| s.unwrap())
-        .collect::<Vec<_>>();
-
-    if header.channels == 1 {
-        (data, header)
-    } else if header.channels == 2 {
-        let data = data
-            .into_iter()
-            .chunks(2)
-            .into_iter()
-            .map(|mut lr| {
-                let l = lr.next().unwrap();
-                let r = lr
-                    .next()
-                    .expect("should have an even number of LRLR samples");
-                stereo_to_mono(l, r)
-            })
-            .collect::<Vec<_>>();
-        (data, header)
-    } else {
-        panic!("unsupported format!");
-    }
-}
-
-/// Writes a WAV file as mono.
-pub fn write_wav_file(path: &Path, samples: &[i16], sample_rate: u32) {
-    let mut wav_writer = hound::WavWriter::create(
-        path,
-        WavSpec {
-            channels: 1,
-            sample_rate,
-            bits_per_sample: 16,
-            sample_format: SampleFormat::Int,
-        },
-    )
-    .unwrap();
-
-    for &sample in samples {
-        wav_writer.write_sample(sample).unwrap()
-    }
-    wav_writer.finalize().unwrap();
-}
-
-/// Transforms two stereo samples (that reflect the same point in time on
-/// different channels) into one mono sample.
-#[inline]
-#[must_use]
-pub const fn stereo_to_mono(l: i16, r: i16) -> i16 {
-    let l = l as i32;
-    let r = r as i32;
-    let avg = (l + r) / 2;
-    avg as i16
-}
-
-/// Transforms an audio sample in range `i16::MIN..=i16::MAX` to a `f32` in
-/// range `-1.0..=1.0`.
-#[inline]
-pub fn i16_sample_to_f32(val: i16) -> f32 {
-    // If to prevent division result >1.0.
-    if val == i16::MIN {
-        -1.0
-    } else {
-        val as f32 / i16::MAX as f32
-    }
-}
-
-/// Transforms an audio sample of type `f32` in range `-1.0..=1.0` to a `i16` in
-/// range `-i16::MAX..=i16::MAX`.
-#[inline]
-pub fn f32_sample_to_i16(val: f32) -> i16 {
-    if val.is_finite() && val.abs() <= 1.0 {
-        (val * i16::MAX as f32) as i16
-    } else {
-        panic!("invalid f32");
-    }
-}
-
 /// Returns the cargo target dir.
 pub fn target_dir() -> PathBuf {
     // 1. Check if CARGO_TARGET_DIR is set
@@ -157,11 +74,15 @@ pub fn sine_wave_samples(fr: f64, sampling_rate: f64) -> Vec<f64> {
         .collect::<Vec<_>>()
 }
 
-pub fn calculate_power(samples: &[f64]) -> f64 {
-    samples
-        .iter()
-        .copied()
-        .map(|x| x / i16::MAX as f64)
-        .map(|x| x * x)
-        .fold(0.0, |acc, val| acc + val)
+/// Returns the power of the sine wave after filtering divided by the power
+/// before filtering. The first half is skipped, so that the transient
+/// response of the filter doesn't distort the result.
+#[cfg(test)]
+pub fn power_ratio(frequency: f64, filter: &mut impl Filter<f64>) -> f64 {
+    let mut samples = sine_wave_samples(frequency, 44100.0);
+    let half = samples.len() / 2;
+    let power_orig = energy(&samples[half..]);
+    filter.reset();
+    apply_filter(filter, &mut samples);
+    energy(&samples[half..]) / power_orig
 }
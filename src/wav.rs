@@ -0,0 +1,806 @@
+/*
+MIT License
+
+Copyright (c) 2021 Philipp Schuster
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Module for filtering WAV files with [`lowpass_wav_file`],
+//! [`filter_wav_file`], and [`filter_wav_file_with_progress`].
+//!
+//! The files are read and written with the `hound` crate. The format of the
+//! input file (channels, sample rate, bit depth, and sample format) is kept,
+//! and each channel is filtered on its own.
+
+use crate::sample::{I24, PcmSample, Scaling};
+use crate::{Filter, FilterError, LowpassFilter, Unbounded};
+use core::fmt::{Display, Formatter};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, Write};
+use std::path::Path;
+use std::vec;
+use std::vec::Vec;
+
+/// The scaling of integer samples, which is the usual convention of WAV
+/// files.
+const SCALING: Scaling = Scaling::Asymmetric;
+
+/// Errors that can happen when a WAV file is filtered.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WavError {
+    /// The file could not be read or written, or is not a valid WAV file.
+    Wav(hound::Error),
+    /// The sample format is not supported. Supported are 8, 16, 24, and
+    /// 32-bit integers and 32-bit floats.
+    UnsupportedFormat {
+        /// The sample format of the file.
+        sample_format: SampleFormat,
+        /// The bits per sample of the file.
+        bits_per_sample: u16,
+    },
+    /// The filter parameters are invalid for the sample rate of the file.
+    Filter(FilterError),
+    /// The selected channel doesn't exist.
+    InvalidChannel {
+        /// The index of the selected channel.
+        index: u16,
+        /// The number of channels of the file.
+        channels: u16,
+    },
+    /// The output path refers to the input file, e.g., via `./`, `..`, or a
+    /// link. Writing it would destroy the input while it is being read.
+    SameFile,
+}
+
+impl Display for WavError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Wav(e) => write!(f, "failed to process the WAV file: {e}"),
+            Self::UnsupportedFormat {
+                sample_format,
+                bits_per_sample,
+            } => write!(
+                f,
+                "unsupported sample format: {bits_per_sample}-bit {sample_format:?}"
+            ),
+            Self::Filter(e) => write!(f, "invalid filter parameters: {e}"),
+            Self::InvalidChannel { index, channels } => write!(
+                f,
+                "channel {index} doesn't exist, the file has {channels} channel(s)"
+            ),
+            Self::SameFile => write!(f, "the output file must differ from the input file"),
+        }
+    }
+}
+
+impl std::error::Error for WavError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Wav(e) => Some(e),
+            Self::UnsupportedFormat { .. } | Self::InvalidChannel { .. } | Self::SameFile => None,
+            Self::Filter(e) => Some(e),
+        }
+    }
+}
+
+impl From<hound::Error> for WavError {
+    fn from(value: hound::Error) -> Self {
+        Self::Wav(value)
+    }
+}
+
+impl From<FilterError> for WavError {
+    fn from(value: FilterError) -> Self {
+        Self::Filter(value)
+    }
+}
+
+/// The number of frames per block of [`filter_wav_file`].
+const DEFAULT_BLOCK_FRAMES: usize = 4096;
+
+/// The progress of [`filter_wav_file_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavProgress {
+    frames_processed: u64,
+    total_frames: u64,
+}
+
+impl WavProgress {
+    /// Returns the number of frames (samples per channel) that have been
+    /// filtered and written so far.
+    #[must_use]
+    pub const fn frames_processed(&self) -> u64 {
+        self.frames_processed
+    }
+
+    /// Returns the number of frames of the input file.
+    #[must_use]
+    pub const fn total_frames(&self) -> u64 {
+        self.total_frames
+    }
+
+    /// Returns the progress in the range `0.0..=1.0`.
+    #[must_use]
+    pub fn fraction(&self) -> f64 {
+        if self.total_frames == 0 {
+            1.0
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = self.frames_processed as f64 / self.total_frames as f64;
+            fraction
+        }
+    }
+}
+
+/// Selects the channels of the output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelMode {
+    /// Keeps all channels and filters each of them on its own.
+    #[default]
+    Keep,
+    /// Downmixes all channels to one by averaging them before filtering.
+    Mono,
+    /// Keeps only the channel with the given index, starting at zero.
+    Select(u16),
+}
+
+impl ChannelMode {
+    /// Returns the number of output channels or an error if the mode
+    /// doesn't fit the number of input channels.
+    const fn output_channels(self, input_channels: u16) -> Result<u16, WavError> {
+        match self {
+            Self::Keep => Ok(input_channels),
+            Self::Mono => Ok(1),
+            Self::Select(index) if index < input_channels => Ok(1),
+            Self::Select(index) => Err(WavError::InvalidChannel {
+                index,
+                channels: input_channels,
+            }),
+        }
+    }
+}
+
+/// The sample formats of WAV files that are supported.
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Int8,
+    Int16,
+    Int24,
+    Int32,
+    Float32,
+}
+
+impl Format {
+    const fn from_spec(spec: WavSpec) -> Result<Self, WavError> {
+        match (spec.sample_format, spec.bits_per_sample) {
+            (SampleFormat::Int, 8) => Ok(Self::Int8),
+            (SampleFormat::Int, 16) => Ok(Self::Int16),
+            (SampleFormat::Int, 24) => Ok(Self::Int24),
+            (SampleFormat::Int, 32) => Ok(Self::Int32),
+            (SampleFormat::Float, 32) => Ok(Self::Float32),
+            (sample_format, bits_per_sample) => Err(WavError::UnsupportedFormat {
+                sample_format,
+                bits_per_sample,
+            }),
+        }
+    }
+
+    /// Converts an integer sample as returned by `hound` to a float.
+    #[allow(clippy::cast_possible_truncation)]
+    fn int_to_float(self, sample: i32) -> f64 {
+        match self {
+            Self::Int8 => (sample as i8).to_float(SCALING),
+            Self::Int16 => (sample as i16).to_float(SCALING),
+            Self::Int24 => I24::new(sample).unwrap_or(I24::MIN).to_float(SCALING),
+            Self::Int32 | Self::Float32 => sample.to_float(SCALING),
+        }
+    }
+
+    /// Converts a float to an integer sample that `hound` can write.
+    fn float_to_int(self, sample: f64) -> i32 {
+        match self {
+            Self::Int8 => i8::from_float_saturating(sample, SCALING).into(),
+            Self::Int16 => i16::from_float_saturating(sample, SCALING).into(),
+            Self::Int24 => I24::from_float_saturating(sample, SCALING).into(),
+            Self::Int32 | Self::Float32 => i32::from_float_saturating(sample, SCALING),
+        }
+    }
+}
+
+/// Filters a WAV file with a [`LowpassFilter`] and writes the result to
+/// another file.
+///
+/// The output has the same format as the input, i.e., the same number of
+/// channels, sample rate, bit depth, and sample format. Each channel is
+/// filtered independently. Supported are 8, 16, 24, and 32-bit integer
+/// samples and 32-bit float samples. Integer outputs saturate.
+///
+/// The samples are streamed from the input to the output in blocks, so the
+/// file is never loaded into memory as a whole. The input is checked before the
+/// output file is created.
+///
+/// # Arguments
+/// - `input`: Path of the WAV file to filter.
+/// - `output`: Path of the filtered WAV file. An existing file is
+///   overwritten, unless it is the input file, which is rejected with
+///   [`WavError::SameFile`].
+/// - `cutoff_frequency_hz`: Cutoff frequency in Hz (e.g., 1000.0).
+///
+/// # Example
+/// ```rust,no_run
+/// use lowpass_filter::lowpass_wav_file;
+///
+/// lowpass_wav_file("recording.wav", "recording_lowpassed.wav", 1000.0).unwrap();
+/// ```
+pub fn lowpass_wav_file(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    cutoff_frequency_hz: f64,
+) -> Result<(), WavError> {
+    filter_wav_file(input, output, ChannelMode::Keep, |sample_rate_hz| {
+        LowpassFilter::try_new(sample_rate_hz, cutoff_frequency_hz)
+            .map(|filter| filter.with_range_policy(Unbounded))
+    })
+}
+
+/// Filters a WAV file with any [`Filter`] and writes the result to another
+/// file. This is the generic counterpart of [`lowpass_wav_file`].
+///
+/// The output has the same format as the input, except for the number of
+/// channels, which is selected by `channels`.
+///
+/// # Arguments
+/// - `input`: Path of the WAV file to filter.
+/// - `output`: Path of the filtered WAV file. An existing file is
+///   overwritten, unless it is the input file, which is rejected with
+///   [`WavError::SameFile`].
+/// - `channels`: The channels of the output file.
+/// - `create_filter`: Creates the filter of one output channel for the sample
+///   rate of the file. It is called once per output channel, before the
+///   output file is created. Float samples may be outside of `-1.0..=1.0`,
+///   so the filters should not restrict the range, e.g., by using
+///   [`Unbounded`].
+///
+/// # Example
+/// ```rust,no_run
+/// use lowpass_filter::{ChannelMode, LowpassFilter, Unbounded, filter_wav_file};
+///
+/// filter_wav_file("stereo.wav", "mono.wav", ChannelMode::Mono, |sample_rate_hz| {
+///     LowpassFilter::try_new(sample_rate_hz, 1000.0).map(|f| f.with_range_policy(Unbounded))
+/// })
+/// .unwrap();
+/// ```
+pub fn filter_wav_file<F: Filter<f64>>(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    channels: ChannelMode,
+    create_filter: impl FnMut(f64) -> Result<F, FilterError>,
+) -> Result<(), WavError> {
+    filter_wav_file_with_progress(
+        input,
+        output,
+        channels,
+        DEFAULT_BLOCK_FRAMES,
+        create_filter,
+        |_| {},
+    )
+}
+
+/// Like [`filter_wav_file`], but processes the file in blocks of
+/// `block_frames` frames and reports the progress after each block, e.g.,
+/// for a progress bar.
+///
+/// Only one block of the input and the output is kept in memory, so files
+/// of any size can be filtered. The state of the filters is kept across
+/// blocks, so the output doesn't depend on the block size.
+///
+/// # Arguments
+/// - `input`: Path of the WAV file to filter.
+/// - `output`: Path of the filtered WAV file. An existing file is
+///   overwritten, unless it is the input file, which is rejected with
+///   [`WavError::SameFile`].
+/// - `channels`: The channels of the output file.
+/// - `block_frames`: The number of frames (samples per channel) per block.
+///   Zero is treated as one.
+/// - `create_filter`: See [`filter_wav_file`].
+/// - `progress`: Called after each block.
+///
+/// # Example
+/// ```rust,no_run
+/// use lowpass_filter::{ChannelMode, LowpassFilter, Unbounded, filter_wav_file_with_progress};
+///
+/// filter_wav_file_with_progress(
+///     "field-recording.wav",
+///     "field-recording_lowpassed.wav",
+///     ChannelMode::Keep,
+///     65536,
+///     |sample_rate_hz| {
+///         LowpassFilter::try_new(sample_rate_hz, 1000.0).map(|f| f.with_range_policy(Unbounded))
+///     },
+///     |progress| eprint!("\r{:.1}%", progress.fraction() * 100.0),
+/// )
+/// .unwrap();
+/// ```
+pub fn filter_wav_file_with_progress<F: Filter<f64>>(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    channels: ChannelMode,
+    block_frames: usize,
+    create_filter: impl FnMut(f64) -> Result<F, FilterError>,
+    progress: impl FnMut(WavProgress),
+) -> Result<(), WavError> {
+    let (reader, format, output_spec, filters) = prepare(&input, channels, create_filter)?;
+    if is_same_file(input.as_ref(), output.as_ref()) {
+        return Err(WavError::SameFile);
+    }
+    let writer = WavWriter::create(output, output_spec)?;
+    filter_wav(
+        reader,
+        writer,
+        format,
+        channels,
+        filters,
+        block_frames.max(1),
+        progress,
+    )
+}
+
+/// Performs all checks of [`filter_wav_file`] without writing the output
+/// file, e.g., for a dry run. Returns the format of the output file.
+///
+/// The samples themselves are not read, so a file that is truncated or
+/// corrupted after its header still fails later.
+///
+/// # Arguments
+/// See [`filter_wav_file`].
+pub fn check_wav_file<F: Filter<f64>>(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    channels: ChannelMode,
+    create_filter: impl FnMut(f64) -> Result<F, FilterError>,
+) -> Result<WavSpec, WavError> {
+    let (_, _, output_spec, _) = prepare(&input, channels, create_filter)?;
+    if is_same_file(input.as_ref(), output.as_ref()) {
+        return Err(WavError::SameFile);
+    }
+    Ok(output_spec)
+}
+
+/// The opened input file, its format, the format of the output file, and
+/// the filters of the output channels.
+type Prepared<F> = (WavReader<BufReader<File>>, Format, WavSpec, Vec<F>);
+
+/// Opens the input file and creates the filters.
+fn prepare<F: Filter<f64>>(
+    input: impl AsRef<Path>,
+    channels: ChannelMode,
+    mut create_filter: impl FnMut(f64) -> Result<F, FilterError>,
+) -> Result<Prepared<F>, WavError> {
+    let reader = WavReader::open(input)?;
+    let spec = reader.spec();
+    let format = Format::from_spec(spec)?;
+    let output_spec = WavSpec {
+        channels: channels.output_channels(spec.channels)?,
+        ..spec
+    };
+    let filters = (0..output_spec.channels)
+        .map(|_| create_filter(f64::from(spec.sample_rate)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((reader, format, output_spec, filters))
+}
+
+/// Returns `true` if both paths refer to the same existing file.
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    // the identity of the file also detects hard links
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Filters all samples from `reader` and writes them to `writer`, block by
+/// block.
+fn filter_wav<R: Read, W: Write + Seek, F: Filter<f64>>(
+    mut reader: WavReader<R>,
+    mut writer: WavWriter<W>,
+    format: Format,
+    channels: ChannelMode,
+    mut filters: Vec<F>,
+    block_frames: usize,
+    mut progress: impl FnMut(WavProgress),
+) -> Result<(), WavError> {
+    let input_channels = usize::from(reader.spec().channels);
+    let total_frames = u64::from(reader.duration());
+    let mut input_block = vec![0.0; block_frames * input_channels];
+    let mut output_block = Vec::with_capacity(block_frames * filters.len());
+    let mut frames_processed = 0;
+    let mut samples = FloatSamples::new(&mut reader, format);
+
+    loop {
+        let samples_read = samples.read_block(&mut input_block, input_channels)?;
+        if samples_read == 0 {
+            break;
+        }
+
+        output_block.clear();
+        for frame in input_block[..samples_read].chunks_exact(input_channels) {
+            match channels {
+                ChannelMode::Keep => {
+                    let filtered = filters.iter_mut().zip(frame);
+                    output_block.extend(filtered.map(|(filter, sample)| filter.run(*sample)));
+                }
+                ChannelMode::Mono => {
+                    #[allow(clippy::cast_precision_loss)]
+                    let sample = frame.iter().sum::<f64>() / frame.len() as f64;
+                    output_block.push(filters[0].run(sample));
+                }
+                ChannelMode::Select(index) => {
+                    output_block.push(filters[0].run(frame[usize::from(index)]));
+                }
+            }
+        }
+        for sample in &output_block {
+            write_float(&mut writer, format, *sample)?;
+        }
+
+        frames_processed += (samples_read / input_channels) as u64;
+        progress(WavProgress {
+            frames_processed,
+            total_frames,
+        });
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
+
+/// The samples of a WAV file, converted to `f64`.
+enum FloatSamples<'a, R: Read> {
+    Int(hound::WavSamples<'a, R, i32>, Format),
+    Float(hound::WavSamples<'a, R, f32>),
+}
+
+impl<'a, R: Read> FloatSamples<'a, R> {
+    fn new(reader: &'a mut WavReader<R>, format: Format) -> Self {
+        match format {
+            Format::Float32 => Self::Float(reader.samples()),
+            _ => Self::Int(reader.samples(), format),
+        }
+    }
+
+    /// Fills the block with the next samples, which must be complete frames
+    /// of `channels` samples. Returns the number of samples that were read,
+    /// which is only smaller than the block at the end of the file.
+    fn read_block(&mut self, block: &mut [f64], channels: usize) -> Result<usize, WavError> {
+        for (index, sample) in block.iter_mut().enumerate() {
+            let next = match self {
+                Self::Int(samples, format) => {
+                    samples.next().map(|x| x.map(|x| format.int_to_float(x)))
+                }
+                Self::Float(samples) => samples.next().map(|x| x.map(f64::from)),
+            };
+            match next {
+                Some(value) => *sample = value?,
+                // an incomplete frame at the end is invalid
+                None if index % channels != 0 => {
+                    return Err(hound::Error::UnfinishedSample.into());
+                }
+                None => return Ok(index),
+            }
+        }
+        Ok(block.len())
+    }
+}
+
+/// Writes a float sample in the format of the file.
+fn write_float<W: Write + Seek>(
+    writer: &mut WavWriter<W>,
+    format: Format,
+    sample: f64,
+) -> Result<(), WavError> {
+    match format {
+        #[allow(clippy::cast_possible_truncation)]
+        Format::Float32 => writer.write_sample(sample as f32)?,
+        _ => writer.write_sample(format.float_to_int(sample))?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::filter_pcm_in_place;
+    use crate::test_util::{sine_wave_samples, target_dir_test_artifacts};
+    use std::path::PathBuf;
+
+    /// Writes a stereo file with silence on the left and a mix of a low and
+    /// a high sine on the right channel.
+    fn write_test_file(name: &str, sample_format: SampleFormat, bits_per_sample: u16) -> PathBuf {
+        let path = target_dir_test_artifacts().join(name);
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample,
+            sample_format,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        let low = sine_wave_samples(100.0, 44100.0);
+        let high = sine_wave_samples(10000.0, 44100.0);
+        for (low, high) in low.iter().zip(&high).take(4410) {
+            let right = (low + high) * 0.45;
+            match (sample_format, bits_per_sample) {
+                (SampleFormat::Float, _) => {
+                    writer.write_sample(0.0_f32).unwrap();
+                    writer.write_sample(right as f32).unwrap();
+                }
+                (_, 8) => {
+                    writer.write_sample(0_i8).unwrap();
+                    writer
+                        .write_sample(i8::from_float_saturating(right, SCALING))
+                        .unwrap();
+                }
+                (_, 16) => {
+                    writer.write_sample(0_i16).unwrap();
+                    writer
+                        .write_sample(i16::from_float_saturating(right, SCALING))
+                        .unwrap();
+                }
+                (_, 24) => {
+                    writer.write_sample(0_i32).unwrap();
+                    writer
+                        .write_sample(I24::from_float_saturating(right, SCALING).to_i32())
+                        .unwrap();
+                }
+                _ => {
+                    writer.write_sample(0_i32).unwrap();
+                    writer
+                        .write_sample(i32::from_float_saturating(right, SCALING))
+                        .unwrap();
+                }
+            }
+        }
+        writer.finalize().unwrap();
+        path
+    }
+
+    /// Returns the peak of each channel, normalized to `-1.0..=1.0`.
+    fn read_peaks(path: &Path) -> (WavSpec, [f64; 2]) {
+        let mut reader = WavReader::open(path).unwrap();
+        let spec = reader.spec();
+        let samples = if spec.sample_format == SampleFormat::Float {
+            reader
+                .samples::<f32>()
+                .map(|x| f64::from(x.unwrap()))
+                .collect::<Vec<_>>()
+        } else {
+            let scale = f64::from(1_u32 << (spec.bits_per_sample - 1));
+            reader
+                .samples::<i32>()
+                .map(|x| f64::from(x.unwrap()) / scale)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(samples.len(), 2 * 4410);
+        let mut peaks = [0.0_f64; 2];
+        for (index, sample) in samples.iter().enumerate() {
+            peaks[index % 2] = peaks[index % 2].max(sample.abs());
+        }
+        (spec, peaks)
+    }
+
+    #[test]
+    fn test_lowpass_wav_file_keeps_format() {
+        for (sample_format, bits_per_sample) in [
+            (SampleFormat::Int, 8),
+            (SampleFormat::Int, 16),
+            (SampleFormat::Int, 24),
+            (SampleFormat::Int, 32),
+            (SampleFormat::Float, 32),
+        ] {
+            let name = std::format!("wav-{bits_per_sample}-{sample_format:?}");
+            let input =
+                write_test_file(&std::format!("{name}.wav"), sample_format, bits_per_sample);
+            let output = input.with_file_name(std::format!("{name}-lowpassed.wav"));
+            lowpass_wav_file(&input, &output, 200.0).unwrap();
+
+            let (input_spec, input_peaks) = read_peaks(&input);
+            let (output_spec, output_peaks) = read_peaks(&output);
+            assert_eq!(input_spec, output_spec);
+            assert_eq!(output_peaks[0], 0.0, "{name}: channels must not be mixed");
+            // the high sine is removed, the low sine is kept
+            assert!(input_peaks[1] > 0.85, "{name}: {input_peaks:?}");
+            assert!(
+                (0.35..0.5).contains(&output_peaks[1]),
+                "{name}: {output_peaks:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_lowpass_wav_file_matches_pcm_filter() {
+        let input = write_test_file("wav-pcm-reference.wav", SampleFormat::Int, 16);
+        let output = input.with_file_name("wav-pcm-reference-lowpassed.wav");
+        lowpass_wav_file(&input, &output, 1000.0).unwrap();
+
+        let right = |path: &Path| {
+            WavReader::open(path)
+                .unwrap()
+                .samples::<i16>()
+                .skip(1)
+                .step_by(2)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        };
+        let mut expected = right(&input);
+        let mut filter = LowpassFilter::new(44100.0, 1000.0).with_range_policy(Unbounded);
+        filter_pcm_in_place(&mut filter, &mut expected, SCALING);
+        assert_eq!(right(&output), expected);
+    }
+
+    #[test]
+    fn test_filter_wav_file_channel_modes() {
+        let input = write_test_file("wav-channels.wav", SampleFormat::Int, 16);
+        let create_filter = |sample_rate_hz| {
+            LowpassFilter::try_new(sample_rate_hz, 1000.0).map(|f| f.with_range_policy(Unbounded))
+        };
+        let read = |path: &Path| {
+            let mut reader = WavReader::open(path).unwrap();
+            let channels = reader.spec().channels;
+            let samples = reader
+                .samples::<i16>()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>();
+            (channels, samples)
+        };
+
+        let keep = input.with_file_name("wav-channels-keep.wav");
+        filter_wav_file(&input, &keep, ChannelMode::Keep, create_filter).unwrap();
+        let (_, keep) = read(&keep);
+        let right = keep.iter().copied().skip(1).step_by(2).collect::<Vec<_>>();
+
+        let selected = input.with_file_name("wav-channels-select.wav");
+        filter_wav_file(&input, &selected, ChannelMode::Select(1), create_filter).unwrap();
+        assert_eq!(read(&selected), (1, right.clone()));
+
+        // the left channel is silent, so the downmix has half the amplitude
+        let mono = input.with_file_name("wav-channels-mono.wav");
+        filter_wav_file(&input, &mono, ChannelMode::Mono, create_filter).unwrap();
+        let (channels, mono) = read(&mono);
+        assert_eq!(channels, 1);
+        for (mono, right) in mono.iter().zip(&right) {
+            assert!((i32::from(*mono) - i32::from(*right) / 2).abs() <= 1);
+        }
+
+        assert!(matches!(
+            filter_wav_file(&input, &selected, ChannelMode::Select(2), create_filter),
+            Err(WavError::InvalidChannel {
+                index: 2,
+                channels: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_filter_wav_file_with_progress() {
+        let input = write_test_file("wav-blocks.wav", SampleFormat::Float, 32);
+        let create_filter = |sample_rate_hz| {
+            LowpassFilter::try_new(sample_rate_hz, 1000.0).map(|f| f.with_range_policy(Unbounded))
+        };
+        let read = |path: &Path| {
+            WavReader::open(path)
+                .unwrap()
+                .samples::<f32>()
+                .map(Result::unwrap)
+                .collect::<Vec<_>>()
+        };
+
+        let reference = input.with_file_name("wav-blocks-reference.wav");
+        filter_wav_file(&input, &reference, ChannelMode::Keep, create_filter).unwrap();
+        let reference = read(&reference);
+
+        // the state is kept across blocks, so the block size doesn't matter
+        for block_frames in [0, 1, 7, 1000, 4410, 100_000] {
+            let output = input.with_file_name("wav-blocks-lowpassed.wav");
+            let mut reports = Vec::new();
+            filter_wav_file_with_progress(
+                &input,
+                &output,
+                ChannelMode::Keep,
+                block_frames,
+                create_filter,
+                |progress| reports.push(progress),
+            )
+            .unwrap();
+            assert_eq!(read(&output), reference, "{block_frames}");
+
+            assert_eq!(reports.len(), 4410_usize.div_ceil(block_frames.max(1)));
+            let last = reports.last().unwrap();
+            assert_eq!(last.frames_processed(), 4410);
+            assert_eq!(last.total_frames(), 4410);
+            assert_eq!(last.fraction(), 1.0);
+            assert!(
+                reports
+                    .windows(2)
+                    .all(|x| x[0].frames_processed() < x[1].frames_processed())
+            );
+        }
+    }
+
+    #[test]
+    fn test_lowpass_wav_file_errors() {
+        let missing = target_dir_test_artifacts().join("wav-missing.wav");
+        let output = target_dir_test_artifacts().join("wav-missing-lowpassed.wav");
+        assert!(matches!(
+            lowpass_wav_file(&missing, &output, 1000.0),
+            Err(WavError::Wav(hound::Error::IoError(_)))
+        ));
+
+        let input = write_test_file("wav-nyquist.wav", SampleFormat::Int, 16);
+        let output = input.with_file_name("wav-nyquist-lowpassed.wav");
+        let _ = std::fs::remove_file(&output);
+        assert!(matches!(
+            lowpass_wav_file(&input, &output, 30000.0),
+            Err(WavError::Filter(FilterError::NyquistViolation))
+        ));
+        assert!(!output.exists());
+
+        // the input must not be overwritten, however the path is written
+        let input = write_test_file("wav-same-file.wav", SampleFormat::Int, 16);
+        let before = std::fs::read(&input).unwrap();
+        let dir = input.parent().unwrap();
+        let dir_name = dir.file_name().unwrap();
+        for output in [
+            input.clone(),
+            dir.join(".").join("wav-same-file.wav"),
+            dir.join("..").join(dir_name).join("wav-same-file.wav"),
+        ] {
+            assert!(matches!(
+                lowpass_wav_file(&input, &output, 1000.0),
+                Err(WavError::SameFile)
+            ));
+        }
+        assert_eq!(std::fs::read(&input).unwrap(), before);
+
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 20,
+            sample_format: SampleFormat::Int,
+        };
+        assert!(matches!(
+            Format::from_spec(spec),
+            Err(WavError::UnsupportedFormat {
+                bits_per_sample: 20,
+                ..
+            })
+        ));
+    }
+}